@@ -0,0 +1,135 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+/// Parse error pointing at the offending part of an input line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number in the input.
+    pub line: usize,
+    /// Byte range of the offending text inside the line.
+    pub span: Range<usize>,
+    /// The whole input line.
+    pub line_text: String,
+    /// What the parser expected to find, e.g. "a number" or "`L` or `R`".
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(
+        line: usize,
+        line_text: &str,
+        span: Range<usize>,
+        expected: impl Into<String>,
+    ) -> Self {
+        debug_assert!(span.start <= span.end && span.end <= line_text.len());
+        Self {
+            line,
+            span,
+            line_text: line_text.to_owned(),
+            expected: expected.into(),
+        }
+    }
+
+    /// Error at `token`, which must be a subslice of `line_text`.
+    pub fn at(line: usize, line_text: &str, token: &str, expected: impl Into<String>) -> Self {
+        let start = token.as_ptr() as usize - line_text.as_ptr() as usize;
+        Self::new(line, line_text, start..start + token.len(), expected)
+    }
+
+    /// Error at the end of the line, for missing tokens.
+    pub fn at_end(line: usize, line_text: &str, expected: impl Into<String>) -> Self {
+        let end = line_text.len();
+        Self::new(line, line_text, end..end, expected)
+    }
+
+    /// Error covering the whole line.
+    pub fn whole_line(line: usize, line_text: &str, expected: impl Into<String>) -> Self {
+        Self::new(line, line_text, 0..line_text.len(), expected)
+    }
+
+    /// Moves the error to another line, for parsers of a single line that do not know their position.
+    pub fn with_line(self, line: usize) -> Self {
+        Self { line, ..self }
+    }
+
+    /// The offending text.
+    pub fn text(&self) -> &str {
+        &self.line_text[self.span.clone()]
+    }
+
+    /// 1-based column of the start of the span, in characters.
+    pub fn column(&self) -> usize {
+        self.line_text[..self.span.start].chars().count() + 1
+    }
+
+    /// Compiler style excerpt of the line with a caret under the offending text.
+    pub fn snippet(&self) -> String {
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        let carets = "^".repeat(self.text().chars().count().max(1));
+        format!(
+            "{gutter} |\n{number} | {}\n{gutter} | {}{carets}",
+            self.line_text,
+            " ".repeat(self.column() - 1),
+        )
+    }
+
+    /// Looks for a parse error in `error` and in the chain of its sources.
+    pub fn find<'a>(error: &'a (dyn Error + 'static)) -> Option<&'a ParseError> {
+        let mut current = Some(error);
+        while let Some(error) = current {
+            if let Some(parse_error) = error.downcast_ref::<ParseError>() {
+                return Some(parse_error);
+            }
+            current = error.source();
+        }
+        None
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, ",
+            self.line,
+            self.column(),
+            self.expected
+        )?;
+        if self.span.is_empty() {
+            write!(f, "found end of line")
+        } else {
+            write!(f, "found `{}`", self.text())
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_test() {
+        let line = "L1x";
+        let error = ParseError::at(3, line, &line[1..], "a number");
+        assert_eq!(1..3, error.span);
+        assert_eq!(
+            "line 3, column 2: expected a number, found `1x`",
+            error.to_string()
+        );
+        assert_eq!("  |\n3 | L1x\n  |  ^^", error.snippet());
+    }
+
+    #[test]
+    fn at_end_test() {
+        let error = ParseError::at_end(12, "", "`L` or `R`");
+        assert_eq!(
+            "line 12, column 1: expected `L` or `R`, found end of line",
+            error.to_string()
+        );
+        assert_eq!("   |\n12 | \n   | ^", error.snippet());
+    }
+}
@@ -0,0 +1,320 @@
+use crate::ParseError;
+use std::fmt;
+use std::ops::{Index, IndexMut, Range};
+
+/// Position in a grid as `(row, column)`.
+pub type Pos = (usize, usize);
+
+/// Offsets of the 4 orthogonal neighbours.
+pub const OFFSETS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// Offsets of the 8 orthogonal and diagonal neighbours.
+pub const OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// Rectangular 2D grid stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its rows, `None` if the rows have different lengths.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let width = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        let height = rows.len();
+        let cells = rows.into_iter().flatten().collect();
+        Some(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parses a character map, one line per row, converting every character with `parse_cell`.
+    /// `expected` describes the valid characters for the error message.
+    pub fn parse<S: AsRef<str>>(
+        lines: &[S],
+        parse_cell: impl Fn(char) -> Option<T>,
+        expected: &str,
+    ) -> Result<Self, ParseError> {
+        let width = lines
+            .first()
+            .map_or(0, |line| line.as_ref().chars().count());
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for (i, line) in lines.iter().enumerate() {
+            cells.extend(Self::parse_row(
+                i + 1,
+                line.as_ref(),
+                Some(width),
+                &parse_cell,
+                expected,
+            )?);
+        }
+        Ok(Self {
+            width,
+            height: lines.len(),
+            cells,
+        })
+    }
+
+    /// Parses the row of a character map on line `line`, for maps read one row at a time.
+    /// The row must have `width` cells if given, usually the length of the first row.
+    pub fn parse_row(
+        line: usize,
+        text: &str,
+        width: Option<usize>,
+        parse_cell: impl Fn(char) -> Option<T>,
+        expected: &str,
+    ) -> Result<Vec<T>, ParseError> {
+        let row = text
+            .char_indices()
+            .map(|(j, c)| {
+                parse_cell(c)
+                    .ok_or_else(|| ParseError::new(line, text, j..j + c.len_utf8(), expected))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        match width {
+            Some(width) if row.len() != width => {
+                let expected = format!("a row of {} cells", width);
+                Err(ParseError::whole_line(line, text, expected))
+            }
+            _ => Ok(row),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, (i, j): Pos) -> bool {
+        i < self.height && j < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.0 * self.width + pos.1])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.contains(pos)
+            .then(|| &mut self.cells[pos.0 * self.width + pos.1])
+    }
+
+    /// Moves `pos` by `delta`, `None` if the result is outside of the grid.
+    pub fn offset(&self, (i, j): Pos, (di, dj): (isize, isize)) -> Option<Pos> {
+        let pos = (i.checked_add_signed(di)?, j.checked_add_signed(dj)?);
+        self.contains(pos).then_some(pos)
+    }
+
+    /// Positions around `pos` at the given offsets that are inside of the grid.
+    pub fn neighbours<'a>(
+        &'a self,
+        pos: Pos,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = Pos> + 'a {
+        offsets
+            .iter()
+            .filter_map(move |&delta| self.offset(pos, delta))
+    }
+
+    /// Orthogonal neighbours of `pos`.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbours(pos, &OFFSETS_4)
+    }
+
+    /// Orthogonal and diagonal neighbours of `pos`.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbours(pos, &OFFSETS_8)
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + use<T> {
+        let width = self.width;
+        (0..self.height).flat_map(move |i| (0..width).map(move |j| (i, j)))
+    }
+
+    /// All cells with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.cells[i * self.width..(i + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|i| self.row(i))
+    }
+
+    pub fn column(&self, j: usize) -> impl Iterator<Item = &T> {
+        assert!(j < self.width, "column {} out of bounds", j);
+        self.cells.iter().skip(j).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|j| self.column(j))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn from_fn(width: usize, height: usize, mut f: impl FnMut(Pos) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|i| (0..width).map(move |j| (i, j)))
+            .map(&mut f)
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Copy of the rectangle at the intersection of the given rows and columns.
+    pub fn crop(&self, rows: Range<usize>, columns: Range<usize>) -> Self {
+        assert!(rows.end <= self.height && columns.end <= self.width);
+        Self::from_fn(columns.len(), rows.len(), |(i, j)| {
+            self[(rows.start + i, columns.start + j)].clone()
+        })
+    }
+
+    /// Rows become columns.
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.height, self.width, |(i, j)| self[(j, i)].clone())
+    }
+
+    pub fn rotate_clockwise(&self) -> Self {
+        Self::from_fn(self.height, self.width, |(i, j)| {
+            self[(self.height - 1 - j, i)].clone()
+        })
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Self {
+        Self::from_fn(self.height, self.width, |(i, j)| {
+            self[(j, self.width - 1 - i)].clone()
+        })
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+    fn index(&self, (i, j): Pos) -> &T {
+        assert!(self.contains((i, j)), "position {:?} out of bounds", (i, j));
+        &self.cells[i * self.width + j]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, (i, j): Pos) -> &mut T {
+        assert!(self.contains((i, j)), "position {:?} out of bounds", (i, j));
+        &mut self.cells[i * self.width + j]
+    }
+}
+
+/// Prints every cell with its own `Display`, one line per row.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        Grid::parse(&["abc", "def"], Some, "any").unwrap()
+    }
+
+    #[test]
+    fn parse_test() {
+        let grid = grid();
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!('f', grid[(1, 2)]);
+        assert_eq!(None, grid.get((2, 0)));
+        let error = ParseError::whole_line(2, "de", "a row of 3 cells");
+        assert_eq!(Err(error), Grid::parse(&["abc", "de"], Some, "any"));
+        let digit = |c: char| c.to_digit(10);
+        let error = ParseError::new(1, "1x", 1..2, "a digit");
+        assert_eq!(Err(error), Grid::parse(&["1x"], digit, "a digit"));
+        let error = ParseError::new(7, "1x", 1..2, "a digit");
+        assert_eq!(Err(error), Grid::parse_row(7, "1x", None, digit, "a digit"));
+        let error = ParseError::whole_line(7, "12", "a row of 3 cells");
+        assert_eq!(
+            Err(error),
+            Grid::parse_row(7, "12", Some(3), digit, "a digit")
+        );
+        assert_eq!(
+            Ok(vec![1, 2]),
+            Grid::parse_row(7, "12", None, digit, "a digit")
+        );
+    }
+
+    #[test]
+    fn neighbours_test() {
+        let grid = grid();
+        assert_eq!(
+            vec![(0, 1), (1, 0)],
+            grid.neighbours4((0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(0, 0), (0, 2), (1, 0), (1, 1), (1, 2)],
+            grid.neighbours8((0, 1)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn transform_test() {
+        let grid = grid();
+        assert_eq!("ad\nbe\ncf", grid.transpose().to_string());
+        assert_eq!("da\neb\nfc", grid.rotate_clockwise().to_string());
+        assert_eq!("cf\nbe\nad", grid.rotate_counterclockwise().to_string());
+        assert_eq!("bc\nef", grid.crop(0..2, 1..3).to_string());
+        assert_eq!(vec!['b', 'e'], grid.column(1).copied().collect::<Vec<_>>());
+    }
+}
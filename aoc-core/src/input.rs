@@ -0,0 +1,160 @@
+use crate::Format;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Lines, StdinLock};
+
+pub fn read_file(file_name: &str) -> io::Result<Vec<String>> {
+    lines_file(file_name)?.collect()
+}
+
+pub fn read_stdin() -> io::Result<Vec<String>> {
+    lines_stdin().collect()
+}
+
+/// Lines of a file read one at a time, for inputs too large to collect.
+pub fn lines_file(file_name: &str) -> io::Result<Lines<BufReader<File>>> {
+    Ok(BufReader::new(File::open(file_name)?).lines())
+}
+
+/// Lines of the standard input read one at a time.
+pub fn lines_stdin() -> Lines<StdinLock<'static>> {
+    io::stdin().lock().lines()
+}
+
+/// Command line of a day binary: `[--format text|json|csv] [FILE]`,
+/// and the options of the day that take a value or are flags.
+pub struct Options {
+    pub mode: Mode,
+    pub format: Format,
+    /// Day options given on the command line with their values, by name without `--`.
+    pub values: Vec<(String, String)>,
+    /// Day flags given on the command line, by name without `--`.
+    pub flags: Vec<String>,
+}
+
+impl Options {
+    pub fn from_args() -> Result<Self, String> {
+        Self::parse(env::args().skip(1))
+    }
+
+    /// Reads the command line, also accepting the day options in `names` and the day flags in `flags`.
+    pub fn from_args_with(names: &[&str], flags: &[&str]) -> Result<Self, String> {
+        Self::parse_with(env::args().skip(1), names, flags)
+    }
+
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        Self::parse_with(args, &[], &[])
+    }
+
+    /// Parses `args`, `names` are the day options that take a value, e.g. `radix` for `--radix 16`,
+    /// and `flags` the day options that do not, e.g. `merge` for `--merge`.
+    pub fn parse_with(
+        args: impl IntoIterator<Item = String>,
+        names: &[&str],
+        flags: &[&str],
+    ) -> Result<Self, String> {
+        let mut options = Options {
+            mode: Mode::Stdin,
+            format: Format::default(),
+            values: Vec::new(),
+            flags: Vec::new(),
+        };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let option = arg
+                .strip_prefix("--")
+                .map(|option| match option.split_once('=') {
+                    Some((name, value)) => (name, Some(value.to_string())),
+                    None => (option, None),
+                });
+            if let Some(format) = arg.strip_prefix("--format=") {
+                options.format = format.parse()?;
+            } else if arg == "--format" {
+                let format = args.next().ok_or("`--format` needs a value")?;
+                options.format = format.parse()?;
+            } else if let Some((name, value)) =
+                option.as_ref().filter(|(name, _)| flags.contains(name))
+            {
+                if value.is_some() {
+                    return Err(format!("`--{}` does not take a value", name));
+                }
+                options.flags.push(name.to_string());
+            } else if let Some((name, value)) = option.filter(|(name, _)| names.contains(name)) {
+                let value = match value {
+                    Some(value) => value,
+                    None => args.next().ok_or(format!("`--{}` needs a value", name))?,
+                };
+                options.values.push((name.to_string(), value));
+            } else if arg.starts_with("--") {
+                return Err(format!("unknown option `{}`", arg));
+            } else if let Mode::File(file) = &options.mode {
+                return Err(format!("unexpected argument `{}` after `{}`", arg, file));
+            } else {
+                options.mode = Mode::File(arg);
+            }
+        }
+        Ok(options)
+    }
+
+    /// The value of the day option `name`, the last one if it is given several times.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        let mut values = self.values.iter().filter(|(n, _)| n == name);
+        values.next_back().map(|(_, value)| value.as_str())
+    }
+
+    /// Whether the day flag `name` is given.
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+}
+
+/// Where the puzzle input comes from.
+pub enum Mode {
+    File(String),
+    Stdin,
+}
+
+impl Mode {
+    pub fn read_lines(&self) -> io::Result<Vec<String>> {
+        match self {
+            Mode::File(file_path) => read_file(file_path),
+            Mode::Stdin => read_stdin(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        Options::parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn options_test() {
+        let options = parse(&["--format", "json", "input.txt"]).unwrap();
+        assert_eq!(Format::Json, options.format);
+        assert!(matches!(options.mode, Mode::File(f) if f == "input.txt"));
+        let options = parse(&["--format=csv"]).unwrap();
+        assert_eq!(Format::Csv, options.format);
+        assert!(matches!(options.mode, Mode::Stdin));
+        assert!(parse(&["--format"]).is_err());
+        assert!(parse(&["a.txt", "b.txt"]).is_err());
+        assert!(parse(&["--verbose"]).is_err());
+        let args = ["--radix", "16", "in.txt", "--radix=8"].map(String::from);
+        let options = Options::parse_with(args, &["radix"], &["merge"]).unwrap();
+        assert_eq!(Some("8"), options.value("radix"));
+        assert_eq!(None, options.value("merge"));
+        assert!(!options.flag("merge"));
+        assert!(matches!(options.mode, Mode::File(f) if f == "in.txt"));
+        assert!(Options::parse_with(["--radix".to_string()], &["radix"], &[]).is_err());
+        assert!(parse(&["--radix", "16"]).is_err());
+        let args = ["--merge", "in.txt"].map(String::from);
+        let options = Options::parse_with(args, &["radix"], &["merge"]).unwrap();
+        assert!(options.flag("merge"));
+        assert!(matches!(options.mode, Mode::File(f) if f == "in.txt"));
+        assert!(Options::parse_with(["--merge=yes".to_string()], &[], &["merge"]).is_err());
+        assert!(parse(&["--merge"]).is_err());
+    }
+}
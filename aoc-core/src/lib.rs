@@ -0,0 +1,75 @@
+mod error;
+mod grid;
+mod input;
+mod report;
+mod solution;
+
+pub use error::ParseError;
+pub use grid::{Grid, OFFSETS_4, OFFSETS_8, Pos};
+pub use input::{Mode, Options, lines_file, lines_stdin, read_file, read_stdin};
+pub use report::{Format, Record, SharedError, print_error, print_records, solve_parts};
+pub use solution::{Answer, DynError, DynInput, Part, Solution, Solver};
+
+use std::process::ExitCode;
+
+/// Reads the input selected by the command line and prints the results of the given parts.
+/// Fails if the command line is invalid, the input cannot be read or a part returns an error.
+pub fn run_parts(day: u8, solver: &dyn Solver, parts: &[Part]) -> ExitCode {
+    match Options::from_args() {
+        Ok(options) => solve_input(&options, day, solver, parts),
+        Err(error) => usage_error(day, &[], &[], error),
+    }
+}
+
+/// Reads the input selected by the command line and prints the results of both parts.
+pub fn run(day: u8, solver: &dyn Solver) -> ExitCode {
+    run_parts(day, solver, &Part::ALL)
+}
+
+/// Like [`run`] for a day with options: the command line also accepts the options in `names`,
+/// which take a value, and the flags in `flags`, and `solver` builds the solution from them.
+pub fn run_with<S: Solver>(
+    day: u8,
+    names: &[&str],
+    flags: &[&str],
+    solver: impl FnOnce(&Options) -> Result<S, String>,
+) -> ExitCode {
+    let options = match Options::from_args_with(names, flags) {
+        Ok(options) => options,
+        Err(error) => return usage_error(day, names, flags, error),
+    };
+    match solver(&options) {
+        Ok(solver) => solve_input(&options, day, &solver, &Part::ALL),
+        Err(error) => usage_error(day, names, flags, error),
+    }
+}
+
+fn usage_error(day: u8, names: &[&str], flags: &[&str], error: String) -> ExitCode {
+    let options = names
+        .iter()
+        .map(|name| format!(" [--{} VALUE]", name))
+        .chain(flags.iter().map(|flag| format!(" [--{}]", flag)))
+        .collect::<String>();
+    eprintln!("error {}", error);
+    eprintln!(
+        "usage: day{:02} [--format text|json|csv]{} [FILE]",
+        day, options
+    );
+    ExitCode::from(2)
+}
+
+fn solve_input(options: &Options, day: u8, solver: &dyn Solver, parts: &[Part]) -> ExitCode {
+    let data = match options.mode.read_lines() {
+        Ok(data) => data,
+        Err(error) => {
+            eprintln!("error {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    let records = solve_parts(day, solver, &data, parts);
+    if print_records(&records, options.format) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
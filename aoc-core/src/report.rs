@@ -0,0 +1,226 @@
+use crate::{Answer, ParseError, Part, Solver};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How the results are printed.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Format {
+    /// `result1 <answer>` lines, the historical output.
+    #[default]
+    Text,
+    /// An array of objects with the fields of a [`Record`].
+    Json,
+    /// A header line followed by one line per [`Record`].
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!(
+                "format must be `text`, `json` or `csv`, got `{}`",
+                s
+            )),
+        }
+    }
+}
+
+/// Error of a part, shared by all parts when the input does not parse.
+pub type SharedError = Arc<dyn Error + Send + Sync>;
+
+/// Result of running a single part.
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub day: u8,
+    pub part: Part,
+    pub result: Result<Answer, SharedError>,
+    /// Time spent solving the part, the shared parse is not included.
+    pub elapsed: Duration,
+}
+
+/// Parses `data` once and solves the given parts.
+pub fn solve_parts(day: u8, solver: &dyn Solver, data: &[String], parts: &[Part]) -> Vec<Record> {
+    let input = solver.parse(data).map_err(SharedError::from);
+    parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let result = match &input {
+                Ok(input) => solver
+                    .solve(input.as_ref(), part)
+                    .map_err(SharedError::from),
+                Err(error) => Err(Arc::clone(error)),
+            };
+            Record {
+                day,
+                part,
+                result,
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect()
+}
+
+/// Prints `error <error>` to stderr, followed by an excerpt of the input if it is a parse error.
+pub fn print_error(error: &(dyn Error + 'static)) {
+    eprintln!("error {}", error);
+    if let Some(parse_error) = ParseError::find(error) {
+        eprintln!("{}", parse_error.snippet());
+    }
+}
+
+/// Prints every error once, a parse error shared by several parts is only printed for the first one.
+fn print_errors(records: &[Record]) {
+    let mut printed: Vec<&SharedError> = Vec::new();
+    for record in records {
+        if let Err(error) = &record.result
+            && !printed.iter().any(|e| Arc::ptr_eq(e, error))
+        {
+            print_error(error.as_ref());
+            printed.push(error);
+        }
+    }
+}
+
+/// Prints the answers to stdout in `format` and the errors to stderr.
+/// Returns whether every part was solved.
+pub fn print_records(records: &[Record], format: Format) -> bool {
+    match format {
+        Format::Text => {
+            for record in records {
+                if let Ok(answer) = &record.result {
+                    println!("result{} {}", record.part.number(), answer);
+                }
+            }
+        }
+        Format::Json => println!("{}", Json(records)),
+        Format::Csv => {
+            println!("day,part,answer,error,elapsed_ms");
+            for record in records {
+                let (answer, error) = match &record.result {
+                    Ok(answer) => (answer.to_string(), String::new()),
+                    Err(error) => (String::new(), error.to_string()),
+                };
+                println!(
+                    "{},{},{},{},{:.3}",
+                    record.day,
+                    record.part.number(),
+                    csv_field(&answer),
+                    csv_field(&error),
+                    record.elapsed.as_secs_f64() * 1e3
+                );
+            }
+        }
+    }
+    print_errors(records);
+    records.iter().all(|record| record.result.is_ok())
+}
+
+/// Quotes a CSV field if it contains a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// JSON string literal of `s`.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// JSON array of records, numeric answers are written as numbers.
+struct Json<'a>(&'a [Record]);
+
+impl fmt::Display for Json<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, record) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            let (answer, error) = match &record.result {
+                Ok(Answer::Number(n)) => (n.to_string(), "null".to_string()),
+                Ok(Answer::Text(s)) => (json_string(s), "null".to_string()),
+                Err(error) => ("null".to_string(), json_string(&error.to_string())),
+            };
+            write!(
+                f,
+                "\n  {{\"day\": {}, \"part\": {}, \"answer\": {}, \"error\": {}, \"elapsed_ms\": {:.3}}}",
+                record.day,
+                record.part.number(),
+                answer,
+                error,
+                record.elapsed.as_secs_f64() * 1e3
+            )?;
+        }
+        if !self.0.is_empty() {
+            writeln!(f)?;
+        }
+        write!(f, "]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<Record> {
+        let error: SharedError = Arc::new(ParseError::at_end(1, "", "a \"number\""));
+        vec![
+            Record {
+                day: 3,
+                part: Part::One,
+                result: Ok(Answer::Number(42)),
+                elapsed: Duration::from_micros(1500),
+            },
+            Record {
+                day: 3,
+                part: Part::Two,
+                result: Err(error),
+                elapsed: Duration::ZERO,
+            },
+        ]
+    }
+
+    #[test]
+    fn format_test() {
+        assert_eq!(Ok(Format::Csv), "csv".parse());
+        assert!("xml".parse::<Format>().is_err());
+        assert_eq!("a", csv_field("a"));
+        assert_eq!("\"a,\"\"b\"\"\"", csv_field("a,\"b\""));
+        assert_eq!(r#""a\"b\\\n""#, json_string("a\"b\\\n"));
+    }
+
+    #[test]
+    fn json_test() {
+        let expected = "[\n  {\"day\": 3, \"part\": 1, \"answer\": 42, \"error\": null, \"elapsed_ms\": 1.500},\n  \
+            {\"day\": 3, \"part\": 2, \"answer\": null, \
+            \"error\": \"line 1, column 1: expected a \\\"number\\\", found end of line\", \"elapsed_ms\": 0.000}\n]";
+        assert_eq!(expected, Json(&records()).to_string());
+        assert_eq!("[]", Json(&[]).to_string());
+    }
+}
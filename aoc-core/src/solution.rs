@@ -0,0 +1,116 @@
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+
+/// Answer of a single part, shared by all days.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+}
+
+macro_rules! impl_from_number {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(x: $t) -> Self {
+                Answer::Number(x as i128)
+            }
+        })*
+    };
+}
+
+impl_from_number!(i32, u32, i64, u64, i128, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(x) => write!(f, "{}", x),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for Part {
+    type Error = u8;
+    fn try_from(x: u8) -> Result<Self, u8> {
+        match x {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            _ => Err(x),
+        }
+    }
+}
+
+/// A puzzle solution: the input is parsed once and then shared by both parts.
+pub trait Solution {
+    type Input;
+    type Error: Error + Send + Sync + 'static;
+
+    fn parse<S: AsRef<str>>(&self, lines: &[S]) -> Result<Self::Input, Self::Error>;
+    fn part1(&self, input: &Self::Input) -> Result<Answer, Self::Error>;
+    fn part2(&self, input: &Self::Input) -> Result<Answer, Self::Error>;
+
+    fn solve(&self, input: &Self::Input, part: Part) -> Result<Answer, Self::Error> {
+        match part {
+            Part::One => self.part1(input),
+            Part::Two => self.part2(input),
+        }
+    }
+}
+
+pub type DynError = Box<dyn Error + Send + Sync>;
+
+/// Parsed input of a [`Solver`], can be shared between threads.
+pub type DynInput = Box<dyn Any + Send + Sync>;
+
+/// Object safe view of a [`Solution`], so that solutions of different days can be stored together.
+pub trait Solver: Sync {
+    fn parse(&self, lines: &[String]) -> Result<DynInput, DynError>;
+
+    /// Solves a part for the input returned by [`Solver::parse`] of the same solver.
+    fn solve(&self, input: &dyn Any, part: Part) -> Result<Answer, DynError>;
+
+    fn run(&self, lines: &[String], part: Part) -> Result<Answer, DynError> {
+        self.solve(self.parse(lines)?.as_ref(), part)
+    }
+}
+
+impl<T> Solver for T
+where
+    T: Solution + Sync,
+    T::Input: Send + Sync + 'static,
+{
+    fn parse(&self, lines: &[String]) -> Result<DynInput, DynError> {
+        Ok(Box::new(Solution::parse(self, lines)?))
+    }
+
+    fn solve(&self, input: &dyn Any, part: Part) -> Result<Answer, DynError> {
+        let input = input
+            .downcast_ref::<T::Input>()
+            .expect("input parsed by another solver");
+        Ok(Solution::solve(self, input, part)?)
+    }
+}
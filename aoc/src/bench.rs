@@ -0,0 +1,334 @@
+use crate::days::Day;
+use aoc_core::{DynError, Part};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::process::{self, Child, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 3] = [Stage::Parse, Stage::Part1, Stage::Part2];
+
+    /// Name of the stage in the history and on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stage::Parse => "parse",
+            Stage::Part1 => "part1",
+            Stage::Part2 => "part2",
+        }
+    }
+}
+
+/// Timing of a single stage of a day over several runs.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Measurement {
+    pub day: u8,
+    pub stage: Stage,
+    pub runs: u32,
+    pub mean_ns: u64,
+    pub min_ns: u64,
+}
+
+/// Results of one `aoc bench` invocation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BenchRun {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub measurements: Vec<Measurement>,
+}
+
+impl BenchRun {
+    pub fn find(&self, day: u8, stage: Stage) -> Option<&Measurement> {
+        self.measurements
+            .iter()
+            .find(|m| m.day == day && m.stage == stage)
+    }
+}
+
+/// All saved benchmark runs, oldest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    pub runs: Vec<BenchRun>,
+}
+
+impl History {
+    /// Loads the history, an absent file is an empty history.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text).map_err(io::Error::from),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// The latest run that measured `day`, runs of other days are skipped.
+    pub fn latest(&self, day: u8) -> Option<&BenchRun> {
+        self.runs
+            .iter()
+            .rev()
+            .find(|run| run.measurements.iter().any(|m| m.day == day))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Outcome of benchmarking a single stage.
+#[derive(Debug)]
+pub enum Outcome {
+    Measured(Measurement),
+    Failed(DynError),
+    TimedOut,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Measured(m) => write!(f, "{}", format_nanos(m.mean_ns)),
+            Outcome::Failed(_) => write!(f, "error"),
+            Outcome::TimedOut => write!(f, "timeout"),
+        }
+    }
+}
+
+pub fn format_nanos(ns: u64) -> String {
+    match ns {
+        0..1_000 => format!("{} ns", ns),
+        1_000..1_000_000 => format!("{:.2} µs", ns as f64 / 1e3),
+        1_000_000..1_000_000_000 => format!("{:.2} ms", ns as f64 / 1e6),
+        _ => format!("{:.2} s", ns as f64 / 1e9),
+    }
+}
+
+/// Runs `f` `runs` times and measures it, stops at the first error.
+fn measure<T>(
+    day: u8,
+    stage: Stage,
+    runs: u32,
+    mut f: impl FnMut() -> Result<T, DynError>,
+) -> Result<(Measurement, T), DynError> {
+    let mut total = Duration::ZERO;
+    let mut min = Duration::MAX;
+    let mut last = None;
+    for _ in 0..runs.max(1) {
+        let start = Instant::now();
+        let result = f()?;
+        let elapsed = start.elapsed();
+        total += elapsed;
+        min = min.min(elapsed);
+        last = Some(result);
+    }
+    let measurement = Measurement {
+        day,
+        stage,
+        runs: runs.max(1),
+        mean_ns: (total / runs.max(1)).as_nanos() as u64,
+        min_ns: min.as_nanos() as u64,
+    };
+    Ok((measurement, last.expect("at least one run")))
+}
+
+/// Measures `stage` of `day` on `data`, the input of a part is parsed beforehand.
+/// This is what the child process of [`bench_day`] runs.
+pub fn bench_stage(
+    day: &Day,
+    data: &[String],
+    stage: Stage,
+    runs: u32,
+) -> Result<Measurement, DynError> {
+    let part = match stage {
+        Stage::Parse => {
+            return measure(day.day, stage, runs, || day.solver.parse(data)).map(|(m, _)| m);
+        }
+        Stage::Part1 => Part::One,
+        Stage::Part2 => Part::Two,
+    };
+    let input = day.solver.parse(data)?;
+    measure(day.day, stage, runs, || {
+        day.solver.solve(input.as_ref(), part)
+    })
+    .map(|(m, _)| m)
+}
+
+/// Waits for `child` to exit, at most `timeout`. On timeout the child is killed
+/// and `None` is returned.
+pub fn wait_with_timeout(
+    child: &mut Child,
+    timeout: Option<Duration>,
+) -> io::Result<Option<ExitStatus>> {
+    let Some(timeout) = timeout else {
+        return child.wait().map(Some);
+    };
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    child.kill()?;
+    child.wait()?;
+    Ok(None)
+}
+
+/// Runs `aoc bench-stage` for `stage` of `day`, with `data` on its standard input.
+fn run_stage(
+    day: u8,
+    data: &[String],
+    stage: Stage,
+    runs: u32,
+    timeout: Option<Duration>,
+) -> io::Result<Outcome> {
+    let mut child = process::Command::new(env::current_exe()?)
+        .args(["bench-stage", "--day", &day.to_string()])
+        .args(["--stage", stage.name(), "--runs", &runs.to_string()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = data
+        .iter()
+        .map(|line| format!("{}\n", line))
+        .collect::<String>();
+    // Written from a thread, a child that exits early must not block the benchmark.
+    let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let status = wait_with_timeout(&mut child, timeout)?;
+    let _ = writer.join();
+    let Some(status) = status else {
+        return Ok(Outcome::TimedOut);
+    };
+    let mut stdout = String::new();
+    let mut stderr = String::new();
+    child
+        .stdout
+        .take()
+        .expect("stdout is piped")
+        .read_to_string(&mut stdout)?;
+    child
+        .stderr
+        .take()
+        .expect("stderr is piped")
+        .read_to_string(&mut stderr)?;
+    if !status.success() {
+        return Ok(Outcome::Failed(stderr.trim().to_string().into()));
+    }
+    let measurement = serde_json::from_str(&stdout).map_err(io::Error::from)?;
+    Ok(Outcome::Measured(measurement))
+}
+
+/// Benchmarks the parse and both parts of `day` on `data`.
+/// Every stage runs in a child process, killed if it does not finish within `timeout`,
+/// so a stage that is given up on cannot slow down the ones measured after it.
+pub fn bench_day(day: &Day, data: &[String], runs: u32, timeout: Option<Duration>) -> [Outcome; 3] {
+    let run = |stage| {
+        run_stage(day.day, data, stage, runs, timeout)
+            .unwrap_or_else(|error| Outcome::Failed(error.into()))
+    };
+    let parse = run(Stage::Parse);
+    match parse {
+        Outcome::Measured(_) => {}
+        Outcome::Failed(_) => {
+            let skipped = || Outcome::Failed("input did not parse".into());
+            return [parse, skipped(), skipped()];
+        }
+        Outcome::TimedOut => return [Outcome::TimedOut, Outcome::TimedOut, Outcome::TimedOut],
+    }
+    [parse, run(Stage::Part1), run(Stage::Part2)]
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Relative change of `current` against `previous`, e.g. `+12%`.
+pub fn change(previous: &Measurement, current: &Measurement) -> String {
+    let ratio = current.mean_ns as f64 / previous.mean_ns.max(1) as f64;
+    format!("{:+.0}%", (ratio - 1.0) * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(stage: Stage, mean_ns: u64) -> Measurement {
+        Measurement {
+            day: 1,
+            stage,
+            runs: 1,
+            mean_ns,
+            min_ns: mean_ns,
+        }
+    }
+
+    #[test]
+    fn format_test() {
+        assert_eq!("999 ns", format_nanos(999));
+        assert_eq!("1.50 µs", format_nanos(1_500));
+        assert_eq!("2.00 ms", format_nanos(2_000_000));
+        assert_eq!("3.25 s", format_nanos(3_250_000_000));
+        let previous = measurement(Stage::Part1, 200);
+        assert_eq!("+50%", change(&previous, &measurement(Stage::Part1, 300)));
+        assert_eq!("-25%", change(&previous, &measurement(Stage::Part1, 150)));
+    }
+
+    #[test]
+    fn history_test() {
+        let run = BenchRun {
+            timestamp: 1,
+            measurements: vec![measurement(Stage::Parse, 10), measurement(Stage::Part2, 20)],
+        };
+        let history = History { runs: vec![run] };
+        let json = serde_json::to_string(&history).unwrap();
+        assert!(json.contains(r#""stage":"part2""#));
+        let history: History = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            Some(20),
+            history.runs[0].find(1, Stage::Part2).map(|m| m.mean_ns)
+        );
+        assert_eq!(None, history.runs[0].find(1, Stage::Part1));
+        let mut history = history;
+        history.runs.push(BenchRun {
+            timestamp: 2,
+            measurements: vec![Measurement {
+                day: 2,
+                ..measurement(Stage::Parse, 30)
+            }],
+        });
+        assert_eq!(Some(1), history.latest(1).map(|run| run.timestamp));
+        assert_eq!(Some(2), history.latest(2).map(|run| run.timestamp));
+        assert!(history.latest(3).is_none());
+    }
+
+    #[test]
+    fn stage_test() {
+        let day = crate::days::find(3).unwrap();
+        let data = ["987654321111111".to_string()];
+        let measured = bench_stage(day, &data, Stage::Part2, 2).unwrap();
+        assert_eq!(
+            (3, Stage::Part2, 2),
+            (measured.day, measured.stage, measured.runs)
+        );
+        assert!(bench_stage(day, &["9x".to_string()], Stage::Part1, 1).is_err());
+        let mut child = process::Command::new("sleep").arg("10").spawn().unwrap();
+        let start = Instant::now();
+        let status = wait_with_timeout(&mut child, Some(Duration::from_millis(50))).unwrap();
+        assert!(status.is_none() && start.elapsed() < Duration::from_secs(5));
+        assert!(child.try_wait().unwrap().is_some());
+    }
+}
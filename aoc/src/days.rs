@@ -0,0 +1,87 @@
+use aoc_core::{Solver, read_file};
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub struct Day {
+    pub day: u8,
+    pub solver: &'static dyn Solver,
+}
+
+impl Day {
+    /// The `dayNN` directory of the workspace.
+    pub fn dir(&self) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join(format!("day{:02}", self.day))
+    }
+
+    /// The `input.txt` that sits in the day directory.
+    pub fn input_path(&self) -> PathBuf {
+        self.dir().join("input.txt")
+    }
+
+    /// The `answers.toml` with the expected answers for `input.txt`.
+    pub fn answers_path(&self) -> PathBuf {
+        self.dir().join("answers.toml")
+    }
+
+    /// Reads `path`, or the day's `input.txt` if it is not given.
+    pub fn read_input(&self, path: Option<&Path>) -> io::Result<Vec<String>> {
+        match path {
+            Some(path) => read_file(&path.to_string_lossy()),
+            None => read_file(&self.input_path().to_string_lossy()),
+        }
+    }
+}
+
+pub const DAYS: &[Day] = &[
+    Day {
+        day: 1,
+        solver: &day01::Day01,
+    },
+    Day {
+        day: 2,
+        solver: &day02::Day02 {
+            radix: 10,
+            merge: false,
+        },
+    },
+    Day {
+        day: 3,
+        solver: &day03::Day03,
+    },
+    Day {
+        day: 4,
+        solver: &day04::Day04 {
+            rules: day04::Rules::PUZZLE,
+        },
+    },
+    Day {
+        day: 5,
+        solver: &day05::Day05,
+    },
+    Day {
+        day: 6,
+        solver: &day06::Day06,
+    },
+    Day {
+        day: 7,
+        solver: &day07::Day07,
+    },
+    Day {
+        day: 8,
+        solver: &day08::Day08 { connections: 1000 },
+    },
+    Day {
+        day: 9,
+        solver: &day09::Day09,
+    },
+    Day {
+        day: 10,
+        solver: &day10::Day10,
+    },
+];
+
+pub fn find(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
@@ -0,0 +1,86 @@
+use aoc_core::{Grid, Pos};
+use day04::Cell;
+use std::io::{self, Write};
+
+const EMPTY: [u8; 3] = [255, 255, 255];
+const ROLL: [u8; 3] = [60, 60, 60];
+const REMOVED: [u8; 3] = [220, 40, 40];
+
+/// RGB image, 3 bytes per pixel row by row.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Draws every cell of `grid` as a `scale` × `scale` square,
+    /// the `removed` rolls in red and the other rolls in dark grey.
+    pub fn render(grid: &Grid<Cell>, removed: &[Pos], scale: usize) -> Self {
+        let mut colours = grid.map(|cell| match cell {
+            Cell::Empty => EMPTY,
+            Cell::Roll => ROLL,
+        });
+        for &pos in removed {
+            colours[pos] = REMOVED;
+        }
+        let (width, height) = (grid.width() * scale, grid.height() * scale);
+        let mut pixels = Vec::with_capacity(width * height * 3);
+        for row in colours.rows() {
+            for _ in 0..scale {
+                for colour in row {
+                    for _ in 0..scale {
+                        pixels.extend_from_slice(colour);
+                    }
+                }
+            }
+        }
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Writes the image as a binary PPM.
+    pub fn write_ppm(&self, mut writer: impl Write) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        writer.write_all(&self.pixels)
+    }
+
+    /// Writes the image as a PNG.
+    pub fn write_png(&self, writer: impl Write) -> io::Result<()> {
+        let size = |n: usize| u32::try_from(n).map_err(io::Error::other);
+        let mut encoder = png::Encoder::new(writer, size(self.width)?, size(self.height)?);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer
+            .write_image_data(&self.pixels)
+            .map_err(io::Error::other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_test() {
+        let grid = day04::parse_input(&["@.", ".."]).unwrap();
+        let image = Image::render(&grid, &[(1, 1)], 2);
+        assert_eq!((4, 4), (image.width, image.height));
+        let pixel = |i: usize, j: usize| &image.pixels[(i * 4 + j) * 3..][..3];
+        assert_eq!(&ROLL, pixel(1, 1));
+        assert_eq!(&EMPTY, pixel(0, 2));
+        assert_eq!(&REMOVED, pixel(3, 2));
+        let mut ppm = Vec::new();
+        image.write_ppm(&mut ppm).unwrap();
+        assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(11 + 4 * 4 * 3, ppm.len());
+        let mut png = Vec::new();
+        image.write_png(&mut png).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+}
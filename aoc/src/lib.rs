@@ -0,0 +1,123 @@
+pub mod bench;
+pub mod days;
+pub mod frames;
+pub mod verify;
+
+use crate::days::Day;
+use aoc_core::{Part, Record, SharedError};
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+use std::sync::{Arc, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Runs `f` on its own thread and waits at most `timeout` for its result.
+/// On timeout the thread is left running in the background and `None` is returned.
+pub fn with_timeout<T, F>(timeout: Option<Duration>, f: F) -> Option<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let Some(timeout) = timeout else {
+        return Some(f());
+    };
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = sender.send(f());
+    });
+    receiver.recv_timeout(timeout).ok()
+}
+
+/// Error of a stage given up on after the timeout.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct TimedOut(pub Duration);
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "timed out after {} s", self.0.as_secs())
+    }
+}
+
+impl Error for TimedOut {}
+
+/// Parses `data` once and solves both parts of `day`, like [`aoc_core::solve_parts`],
+/// giving up on the parse and on every part after `timeout`.
+pub fn solve_with_timeout(
+    day: &'static Day,
+    data: Vec<String>,
+    timeout: Option<Duration>,
+) -> Vec<Record> {
+    let timed_out = || -> SharedError { Arc::new(TimedOut(timeout.unwrap_or_default())) };
+    let input = match with_timeout(timeout, move || day.solver.parse(&data)) {
+        Some(Ok(input)) => Ok(Arc::<dyn Any + Send + Sync>::from(input)),
+        Some(Err(error)) => Err(SharedError::from(error)),
+        None => Err(timed_out()),
+    };
+    Part::ALL
+        .map(|part| {
+            let start = Instant::now();
+            let result = match &input {
+                Ok(input) => {
+                    let input = Arc::clone(input);
+                    match with_timeout(timeout, move || day.solver.solve(&*input, part)) {
+                        Some(result) => result.map_err(SharedError::from),
+                        None => Err(timed_out()),
+                    }
+                }
+                Err(error) => Err(Arc::clone(error)),
+            };
+            Record {
+                day: day.day,
+                part,
+                result,
+                elapsed: start.elapsed(),
+            }
+        })
+        .to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_core::{Answer, Solution};
+
+    /// Part 1 answers at once, part 2 takes far longer than the timeout.
+    struct Slow;
+
+    impl Solution for Slow {
+        type Input = ();
+        type Error = TimedOut;
+
+        fn parse<S: AsRef<str>>(&self, _: &[S]) -> Result<(), TimedOut> {
+            Ok(())
+        }
+
+        fn part1(&self, _: &()) -> Result<Answer, TimedOut> {
+            Ok(Answer::Number(1))
+        }
+
+        fn part2(&self, _: &()) -> Result<Answer, TimedOut> {
+            thread::sleep(Duration::from_secs(10));
+            Ok(Answer::Number(2))
+        }
+    }
+
+    static SLOW: Day = Day {
+        day: 1,
+        solver: &Slow,
+    };
+
+    #[test]
+    fn timeout_test() {
+        let start = Instant::now();
+        let records = solve_with_timeout(&SLOW, Vec::new(), Some(Duration::from_millis(50)));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(Some(&Answer::Number(1)), records[0].result.as_ref().ok());
+        let error = records[1].result.as_ref().unwrap_err();
+        assert_eq!(
+            Some(&TimedOut(Duration::from_millis(50))),
+            error.downcast_ref()
+        );
+    }
+}
@@ -0,0 +1,723 @@
+use aoc::TimedOut;
+use aoc::bench::{self, BenchRun, History, Outcome, Stage};
+use aoc::days::{self, DAYS, Day};
+use aoc::frames::Image;
+use aoc::verify::{self, Status};
+use aoc_core::{
+    Answer, Format, Part, Record, SharedError, Solver, lines_file, lines_stdin, print_error,
+    print_records, read_stdin, solve_parts,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code 2025 solutions runner")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the solutions of a single day or of all days
+    Run(RunArgs),
+    /// Time the parse and both parts of every day on its input
+    Bench(BenchArgs),
+    /// Check the answers of every day against its answers.toml
+    Verify(VerifyArgs),
+    /// Print every rotation of the day 1 safe dial with the running zero count
+    Trace(TraceArgs),
+    /// Shrink day 1 instructions to the shortest equivalent list
+    Compile(CompileArgs),
+    /// Print or draw the day 4 warehouse after every wave of roll removals
+    Frames(FramesArgs),
+    /// Solve day 4 reading the map one row at a time, for maps too large for memory
+    Stream(StreamArgs),
+    /// List every day 5 ingredient with its freshness and the ranges containing it
+    Report(ReportArgs),
+    /// Time a single stage for `aoc bench`, reading the input from the standard input
+    #[command(hide = true)]
+    BenchStage(BenchStageArgs),
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// Day to run
+    #[arg(long, required_unless_present = "all", value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: Option<u8>,
+    /// Run a single part instead of both
+    #[arg(long, requires = "day", value_parser = parse_part)]
+    part: Option<Part>,
+    /// Input file, defaults to the input.txt in the day directory
+    #[arg(long, requires = "day")]
+    input: Option<PathBuf>,
+    /// Base of the day 2 IDs, from 2 to 36
+    #[arg(long, requires = "day")]
+    radix: Option<u32>,
+    /// Join overlapping day 2 ranges, so an ID in several of them is counted once
+    #[arg(long, requires = "day")]
+    merge: bool,
+    /// Run every day in order and print a summary table
+    #[arg(long, conflicts_with = "day")]
+    all: bool,
+    /// With `--all`, give up on a part after this many seconds, 0 waits forever
+    #[arg(long, default_value_t = 60)]
+    timeout: u64,
+    /// Output format: `text`, `json` or `csv`
+    #[arg(long, default_value = "text")]
+    format: Format,
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    /// Only benchmark this day
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: Option<u8>,
+    /// Number of timed runs of every stage
+    #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u32).range(1..))]
+    runs: u32,
+    /// Give up on a stage after this many seconds, 0 waits forever
+    #[arg(long, default_value_t = 30)]
+    timeout: u64,
+    /// JSON file the results are appended to, and compared against
+    #[arg(long, default_value = "bench.json")]
+    history: PathBuf,
+}
+
+#[derive(Args)]
+struct BenchStageArgs {
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+    #[arg(long, value_parser = parse_stage)]
+    stage: Stage,
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    runs: u32,
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+    /// Only verify this day
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: Option<u8>,
+    /// Count a part as failed after this many seconds, 0 waits forever
+    #[arg(long, default_value_t = 60)]
+    timeout: u64,
+}
+
+#[derive(Args)]
+struct TraceArgs {
+    /// Count the zeros like this part does
+    #[arg(long, default_value = "2", value_parser = parse_part)]
+    part: Part,
+    /// Input file, defaults to day01/input.txt
+    #[arg(long)]
+    input: Option<PathBuf>,
+    /// Aligned text columns or one JSON object per line
+    #[arg(long, value_enum, default_value_t = TraceFormat::Text)]
+    format: TraceFormat,
+}
+
+#[derive(Args)]
+struct CompileArgs {
+    /// Input file, defaults to day01/input.txt
+    #[arg(long)]
+    input: Option<PathBuf>,
+    /// Also keep the number of rotations that end at zero
+    #[arg(long)]
+    keep_hits: bool,
+}
+
+#[derive(Args)]
+struct FramesArgs {
+    /// Input file, defaults to day04/input.txt
+    #[arg(long)]
+    input: Option<PathBuf>,
+    /// Print ASCII frames to stdout or write one image per frame
+    #[arg(long, value_enum, default_value_t = FrameFormat::Ascii)]
+    format: FrameFormat,
+    /// Directory the images are written to
+    #[arg(long, default_value = "frames")]
+    output: PathBuf,
+    /// Width in pixels of a cell in the images
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..=64))]
+    scale: u32,
+}
+
+#[derive(Args)]
+struct StreamArgs {
+    /// Part to solve, part 1 keeps a window of rows and part 2 the positions of the rolls
+    #[arg(long, default_value = "1", value_parser = parse_part)]
+    part: Part,
+    /// Input file, `-` reads the standard input, defaults to day04/input.txt
+    #[arg(long)]
+    input: Option<PathBuf>,
+    /// Output format: `text`, `json` or `csv`
+    #[arg(long, default_value = "text")]
+    format: Format,
+}
+
+#[derive(Args)]
+struct ReportArgs {
+    /// Input file, defaults to day05/input.txt
+    #[arg(long)]
+    input: Option<PathBuf>,
+    /// Aligned text columns or a single JSON object
+    #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+    format: ReportFormat,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum FrameFormat {
+    Ascii,
+    Ppm,
+    Png,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TraceFormat {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ReportFormat {
+    Text,
+    Json,
+}
+
+/// A fresh range in `aoc report --format json`.
+#[derive(Serialize)]
+struct RangeJson {
+    line: usize,
+    start: u64,
+    end: u64,
+}
+
+impl From<&day05::SourceRange> for RangeJson {
+    fn from(range: &day05::SourceRange) -> Self {
+        Self {
+            line: range.line,
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
+
+/// An ingredient in `aoc report --format json`.
+#[derive(Serialize)]
+struct IdJson {
+    id: u64,
+    fresh: bool,
+    ranges: Vec<RangeJson>,
+}
+
+/// Output of `aoc report --format json`.
+#[derive(Serialize)]
+struct ReportJson {
+    ids: Vec<IdJson>,
+    redundant: Vec<RangeJson>,
+}
+
+/// A line of `aoc trace --format json`, fields in column order.
+#[derive(Serialize)]
+struct TraceJson {
+    instruction: String,
+    start: i64,
+    end: i64,
+    hits: i128,
+    total: i128,
+}
+
+fn parse_part(s: &str) -> Result<Part, String> {
+    s.parse::<u8>()
+        .ok()
+        .and_then(|x| Part::try_from(x).ok())
+        .ok_or_else(|| format!("part must be 1 or 2, got `{}`", s))
+}
+
+fn parse_stage(s: &str) -> Result<Stage, String> {
+    Stage::ALL
+        .into_iter()
+        .find(|stage| stage.name() == s)
+        .ok_or_else(|| format!("stage must be parse, part1 or part2, got `{}`", s))
+}
+
+fn exit_code(success: bool) -> ExitCode {
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Reads `path`, or the day's `input.txt`, and prints `error <path>: <message>`
+/// when it cannot be read.
+fn read_input(day: &Day, path: Option<&Path>) -> Option<Vec<String>> {
+    let path = path.map_or_else(|| day.input_path(), Path::to_path_buf);
+    match day.read_input(Some(&path)) {
+        Ok(data) => Some(data),
+        Err(error) => {
+            eprintln!("error {}: {}", path.display(), error);
+            None
+        }
+    }
+}
+
+fn run_day(args: RunArgs) -> io::Result<ExitCode> {
+    let Some(day) = args.day.and_then(days::find) else {
+        eprintln!(
+            "no solution registered for day {}",
+            args.day.unwrap_or_default()
+        );
+        return Ok(ExitCode::FAILURE);
+    };
+    let day02;
+    let solver = match (args.radix, args.merge) {
+        (None, false) => day.solver,
+        (radix, merge) if day.day == 2 => match day02::Day02::new(radix.unwrap_or(10), merge) {
+            Ok(solver) => {
+                day02 = solver;
+                &day02 as &dyn Solver
+            }
+            Err(error) => {
+                print_error(&error);
+                return Ok(ExitCode::FAILURE);
+            }
+        },
+        _ => {
+            eprintln!("`--radix` and `--merge` only apply to day 2");
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    let Some(data) = read_input(day, args.input.as_deref()) else {
+        return Ok(ExitCode::FAILURE);
+    };
+    let parts = match args.part {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    };
+    let records = solve_parts(day.day, solver, &data, &parts);
+    Ok(exit_code(print_records(&records, args.format)))
+}
+
+fn run_all(format: Format, timeout: u64) -> ExitCode {
+    let timeout = (timeout > 0).then(|| Duration::from_secs(timeout));
+    let records = DAYS
+        .iter()
+        .flat_map(|day| match day.read_input(None) {
+            Ok(data) => aoc::solve_with_timeout(day, data, timeout),
+            Err(error) => {
+                let error: SharedError = Arc::new(error);
+                Part::ALL
+                    .map(|part| Record {
+                        day: day.day,
+                        part,
+                        result: Err(Arc::clone(&error)),
+                        elapsed: Duration::ZERO,
+                    })
+                    .to_vec()
+            }
+        })
+        .collect::<Vec<_>>();
+    if format != Format::Text {
+        return exit_code(print_records(&records, format));
+    }
+    let cell = |record: &Record| match &record.result {
+        Ok(answer) => answer.to_string(),
+        Err(error) if error.is::<TimedOut>() => "timeout".to_string(),
+        Err(_) => "error".to_string(),
+    };
+    let width = records
+        .iter()
+        .map(|record| cell(record).len())
+        .fold("Part 1".len(), usize::max);
+    println!("Day | {:<width$} | {:<width$}", "Part 1", "Part 2");
+    println!("----+-{:-<width$}-+-{:-<width$}", "", "");
+    for parts in records.chunks(Part::ALL.len()) {
+        println!(
+            "{:>3} | {:<width$} | {:<width$}",
+            parts[0].day,
+            cell(&parts[0]),
+            cell(&parts[1])
+        );
+    }
+    for record in &records {
+        if let Err(error) = &record.result {
+            eprintln!(
+                "day {} part {}: error {}",
+                record.day,
+                record.part.number(),
+                error
+            );
+        }
+    }
+    exit_code(records.iter().all(|record| record.result.is_ok()))
+}
+
+fn run_bench(args: BenchArgs) -> io::Result<ExitCode> {
+    let selected = match args.day {
+        Some(day) => match days::find(day) {
+            Some(day) => vec![day],
+            None => {
+                eprintln!("no solution registered for day {}", day);
+                return Ok(ExitCode::FAILURE);
+            }
+        },
+        None => DAYS.iter().collect(),
+    };
+    let timeout = (args.timeout > 0).then(|| Duration::from_secs(args.timeout));
+    let mut history = History::load(&args.history)?;
+    let mut run = BenchRun {
+        timestamp: bench::now(),
+        measurements: Vec::new(),
+    };
+    println!(
+        "Day | {:>10} | {:>6} | {:>10} | {:>6} | {:>10} | {:>6}",
+        "Parse", "change", "Part 1", "change", "Part 2", "change"
+    );
+    println!(
+        "----+{}",
+        ["-".repeat(12), "-".repeat(8)].concat().repeat(3)
+    );
+    for day in selected {
+        let outcomes = match day.read_input(None) {
+            Ok(data) => bench::bench_day(day, &data, args.runs, timeout),
+            Err(error) => {
+                eprintln!("day {}: {}", day.day, error);
+                continue;
+            }
+        };
+        // Days benchmarked on their own are compared with their own latest run.
+        let previous = history.latest(day.day);
+        print!("{:>3}", day.day);
+        for (stage, outcome) in Stage::ALL.into_iter().zip(outcomes) {
+            let change = match &outcome {
+                Outcome::Measured(current) => previous
+                    .and_then(|p| p.find(day.day, stage))
+                    .map(|p| bench::change(p, current))
+                    .unwrap_or_default(),
+                _ => String::new(),
+            };
+            print!(" | {:>10} | {:>6}", outcome.to_string(), change);
+            match outcome {
+                Outcome::Measured(measurement) => run.measurements.push(measurement),
+                Outcome::Failed(error) => eprintln!("day {} {:?}: {}", day.day, stage, error),
+                Outcome::TimedOut => {}
+            }
+        }
+        println!();
+    }
+    history.runs.push(run);
+    history.save(&args.history)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_bench_stage(args: BenchStageArgs) -> io::Result<ExitCode> {
+    let Some(day) = days::find(args.day) else {
+        eprintln!("no solution registered for day {}", args.day);
+        return Ok(ExitCode::FAILURE);
+    };
+    let data = read_stdin()?;
+    match bench::bench_stage(day, &data, args.stage, args.runs) {
+        Ok(measurement) => {
+            println!("{}", serde_json::to_string(&measurement)?);
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            Ok(ExitCode::FAILURE)
+        }
+    }
+}
+
+/// Status coloured with ANSI escapes when stdout is a terminal and `NO_COLOR` is not set.
+fn colour_status(status: &Status, width: usize) -> String {
+    let text = format!("{:<width$}", status.to_string());
+    if !io::stdout().is_terminal() || std::env::var_os("NO_COLOR").is_some() {
+        return text;
+    }
+    let colour = match status {
+        Status::Pass => "32",
+        Status::Missing => "33",
+        Status::Fail { .. } | Status::Error(_) | Status::Timeout => "31",
+    };
+    format!("\x1b[{}m{}\x1b[0m", colour, text)
+}
+
+fn run_verify(args: VerifyArgs) -> ExitCode {
+    let selected = match args.day {
+        Some(day) => match days::find(day) {
+            Some(day) => vec![day],
+            None => {
+                eprintln!("no solution registered for day {}", day);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => DAYS.iter().collect(),
+    };
+    let timeout = (args.timeout > 0).then(|| Duration::from_secs(args.timeout));
+    let rows = selected
+        .into_iter()
+        .map(|day| (day.day, verify::verify_day(day, timeout)))
+        .collect::<Vec<_>>();
+    let width = rows
+        .iter()
+        .flat_map(|(_, statuses)| statuses.iter().map(|s| s.to_string().len()))
+        .fold("Part 1".len(), usize::max);
+    println!("Day | {:<width$} | {:<width$}", "Part 1", "Part 2");
+    println!("----+-{:-<width$}-+-{:-<width$}", "", "");
+    for (day, [part1, part2]) in &rows {
+        println!(
+            "{:>3} | {} | {}",
+            day,
+            colour_status(part1, width),
+            colour_status(part2, width)
+        );
+    }
+    let failed = rows
+        .iter()
+        .flat_map(|(_, statuses)| statuses)
+        .any(Status::is_failure);
+    exit_code(!failed)
+}
+
+fn run_trace(args: TraceArgs) -> io::Result<ExitCode> {
+    let day = days::find(1).expect("day 1 is registered");
+    let Some(data) = read_input(day, args.input.as_deref()) else {
+        return Ok(ExitCode::FAILURE);
+    };
+    let commands = match day01::parse_input(&data) {
+        Ok(commands) => commands,
+        Err(error) => {
+            print_error(&error);
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    let trace = day01::trace(&mut day01::Dial::default(), &commands, args.part);
+    match args.format {
+        TraceFormat::Text => {
+            let width = commands
+                .iter()
+                .map(|c| c.to_string().len())
+                .fold("instruction".len(), usize::max);
+            println!(
+                "{:<width$} {:>5} {:>5} {:>5} {:>8}",
+                "instruction", "start", "end", "hits", "total"
+            );
+            for line in &trace {
+                println!(
+                    "{:<width$} {:>5} {:>5} {:>5} {:>8}",
+                    line.step.command.to_string(),
+                    line.step.start,
+                    line.step.end,
+                    line.hits,
+                    line.total
+                );
+            }
+        }
+        TraceFormat::Json => {
+            for line in &trace {
+                let json = TraceJson {
+                    instruction: line.step.command.to_string(),
+                    start: line.step.start,
+                    end: line.step.end,
+                    hits: line.hits,
+                    total: line.total,
+                };
+                println!("{}", serde_json::to_string(&json)?);
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_compile(args: CompileArgs) -> io::Result<ExitCode> {
+    let day = days::find(1).expect("day 1 is registered");
+    let Some(data) = read_input(day, args.input.as_deref()) else {
+        return Ok(ExitCode::FAILURE);
+    };
+    let commands = match day01::parse_input(&data) {
+        Ok(commands) => commands,
+        Err(error) => {
+            print_error(&error);
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    let dial = day01::Dial::default();
+    let compiled = if args.keep_hits {
+        dial.compile_hits(&commands)
+    } else {
+        dial.compile(&commands)
+    };
+    if !compiled.is_empty() {
+        println!("{}", day01::format_commands(&compiled));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_frames(args: FramesArgs) -> io::Result<ExitCode> {
+    let day = days::find(4).expect("day 4 is registered");
+    let Some(data) = read_input(day, args.input.as_deref()) else {
+        return Ok(ExitCode::FAILURE);
+    };
+    let grid = match day04::parse_input(&data) {
+        Ok(grid) => grid,
+        Err(error) => {
+            print_error(&error);
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    // Frame 0 is the initial warehouse, frame n is the warehouse after wave n.
+    let frames = std::iter::once((Vec::new(), grid.clone()))
+        .chain(day04::waves(&grid, &day04::Rules::PUZZLE).map(|wave| (wave.removed, wave.grid)));
+    if args.format == FrameFormat::Ascii {
+        for (i, (removed, grid)) in frames.enumerate() {
+            if i > 0 {
+                println!();
+            }
+            println!("wave {}: {} removed", i, removed.len());
+            println!("{}", grid);
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+    fs::create_dir_all(&args.output)?;
+    let mut count = 0;
+    for (i, (removed, grid)) in frames.enumerate() {
+        let image = Image::render(&grid, &removed, args.scale as usize);
+        let extension = if args.format == FrameFormat::Ppm {
+            "ppm"
+        } else {
+            "png"
+        };
+        let path = args.output.join(format!("frame{:04}.{}", i, extension));
+        let writer = BufWriter::new(File::create(path)?);
+        match args.format {
+            FrameFormat::Ppm => image.write_ppm(writer)?,
+            _ => image.write_png(writer)?,
+        }
+        count += 1;
+    }
+    println!("{} frames written to {}", count, args.output.display());
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_stream(args: StreamArgs) -> io::Result<ExitCode> {
+    let path = match &args.input {
+        Some(path) => path.clone(),
+        None => days::find(4).expect("day 4 is registered").input_path(),
+    };
+    let lines: Box<dyn Iterator<Item = io::Result<String>>> = if path.as_os_str() == "-" {
+        Box::new(lines_stdin())
+    } else {
+        match lines_file(&path.to_string_lossy()) {
+            Ok(lines) => Box::new(lines),
+            Err(error) => {
+                eprintln!("error {}: {}", path.display(), error);
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+    };
+    // Stops at the first read error, which is returned once the day is done with the lines.
+    let mut read_error = None;
+    let lines = lines.map_while(|line| line.map_err(|error| read_error = Some(error)).ok());
+    let rules = day04::Rules::PUZZLE;
+    let start = Instant::now();
+    let result = match args.part {
+        Part::One => day04::stream_part1(lines, &rules),
+        Part::Two => day04::parse_sparse(lines).map(|rolls| day04::sparse_part2(&rolls, &rules)),
+    };
+    if let Some(error) = read_error {
+        eprintln!("error {}: {}", path.display(), error);
+        return Ok(ExitCode::FAILURE);
+    }
+    let record = Record {
+        day: 4,
+        part: args.part,
+        result: result
+            .map(Answer::from)
+            .map_err(|error| -> SharedError { Arc::new(error) }),
+        elapsed: start.elapsed(),
+    };
+    Ok(exit_code(print_records(&[record], args.format)))
+}
+
+fn run_report(args: ReportArgs) -> io::Result<ExitCode> {
+    let day = days::find(5).expect("day 5 is registered");
+    let Some(data) = read_input(day, args.input.as_deref()) else {
+        return Ok(ExitCode::FAILURE);
+    };
+    let db = match day05::parse_input(&data) {
+        Ok(db) => db,
+        Err(error) => {
+            print_error(&error);
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    let report = day05::report(&db);
+    match args.format {
+        ReportFormat::Text => {
+            let width = report
+                .ids
+                .iter()
+                .map(|r| r.id.to_string().len())
+                .fold("id".len(), usize::max);
+            println!("{:<width$} {:<7} ranges", "id", "status");
+            for id in &report.ids {
+                let status = if id.is_fresh() { "fresh" } else { "spoiled" };
+                let ranges = id
+                    .matches
+                    .iter()
+                    .map(|r| format!("line {} ({}-{})", r.line, r.start, r.end))
+                    .collect::<Vec<_>>();
+                let line = format!("{:<width$} {:<7} {}", id.id, status, ranges.join(", "));
+                println!("{}", line.trim_end());
+            }
+            println!();
+            if report.redundant.is_empty() {
+                println!("no redundant ranges");
+            } else {
+                println!("redundant ranges, covered by the other ranges:");
+                for r in &report.redundant {
+                    println!("line {}: {}-{}", r.line, r.start, r.end);
+                }
+            }
+        }
+        ReportFormat::Json => {
+            let json = ReportJson {
+                ids: report
+                    .ids
+                    .iter()
+                    .map(|id| IdJson {
+                        id: id.id,
+                        fresh: id.is_fresh(),
+                        ranges: id.matches.iter().map(RangeJson::from).collect(),
+                    })
+                    .collect(),
+                redundant: report.redundant.iter().map(RangeJson::from).collect(),
+            };
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> io::Result<ExitCode> {
+    match Cli::parse().command {
+        Command::Run(args) if args.all => Ok(run_all(args.format, args.timeout)),
+        Command::Run(args) => run_day(args),
+        Command::Bench(args) => run_bench(args),
+        Command::Verify(args) => Ok(run_verify(args)),
+        Command::Trace(args) => run_trace(args),
+        Command::Compile(args) => run_compile(args),
+        Command::Frames(args) => run_frames(args),
+        Command::Stream(args) => run_stream(args),
+        Command::Report(args) => run_report(args),
+        Command::BenchStage(args) => run_bench_stage(args),
+    }
+}
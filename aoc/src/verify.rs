@@ -0,0 +1,156 @@
+use crate::days::Day;
+use crate::with_timeout;
+use aoc_core::Part;
+use serde::Deserialize;
+use std::any::Any;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Expected answer as written in `answers.toml`, either a number or a string.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum Expected {
+    Number(i64),
+    Text(String),
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expected::Number(n) => write!(f, "{}", n),
+            Expected::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// Contents of a day's `answers.toml`, a part without an entry is not checked.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Answers {
+    pub part1: Option<Expected>,
+    pub part2: Option<Expected>,
+}
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    /// Loads the answers of `day`, an absent file has no answers.
+    pub fn load(day: &Day) -> io::Result<Self> {
+        match fs::read_to_string(day.answers_path()) {
+            Ok(text) => Self::parse(&text).map_err(io::Error::other),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn get(&self, part: Part) -> Option<&Expected> {
+        match part {
+            Part::One => self.part1.as_ref(),
+            Part::Two => self.part2.as_ref(),
+        }
+    }
+}
+
+/// Result of checking a single part.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Status {
+    Pass,
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// No expected answer is stored, the part is not run.
+    Missing,
+    Error(String),
+    Timeout,
+}
+
+impl Status {
+    /// Whether the status should fail the verification.
+    pub fn is_failure(&self) -> bool {
+        !matches!(self, Status::Pass | Status::Missing)
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail { expected, actual } => {
+                write!(f, "fail: expected {}, got {}", expected, actual)
+            }
+            Status::Missing => write!(f, "missing"),
+            Status::Error(error) => write!(f, "error: {}", error),
+            Status::Timeout => write!(f, "timeout"),
+        }
+    }
+}
+
+/// Runs both parts of `day` on its `input.txt` and compares them with its `answers.toml`.
+/// Nothing is run for the parts without an expected answer.
+pub fn verify_day(day: &'static Day, timeout: Option<Duration>) -> [Status; 2] {
+    let answers = match Answers::load(day) {
+        Ok(answers) => answers,
+        Err(error) => return Part::ALL.map(|_| Status::Error(error.to_string())),
+    };
+    if Part::ALL.iter().all(|&part| answers.get(part).is_none()) {
+        return [Status::Missing, Status::Missing];
+    }
+    let data = match day.read_input(None) {
+        Ok(data) => data,
+        Err(error) => return Part::ALL.map(|_| Status::Error(error.to_string())),
+    };
+    let input = match with_timeout(timeout, move || day.solver.parse(&data)) {
+        Some(Ok(input)) => Arc::<dyn Any + Send + Sync>::from(input),
+        Some(Err(error)) => return Part::ALL.map(|_| Status::Error(error.to_string())),
+        None => return [Status::Timeout, Status::Timeout],
+    };
+    Part::ALL.map(|part| {
+        let Some(expected) = answers.get(part) else {
+            return Status::Missing;
+        };
+        let input = Arc::clone(&input);
+        let answer = with_timeout(timeout, move || day.solver.solve(&*input, part));
+        match answer {
+            Some(Ok(answer)) if answer.to_string() == expected.to_string() => Status::Pass,
+            Some(Ok(answer)) => Status::Fail {
+                expected: expected.to_string(),
+                actual: answer.to_string(),
+            },
+            Some(Err(error)) => Status::Error(error.to_string()),
+            None => Status::Timeout,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_test() {
+        let answers = Answers::parse("part1 = 42\npart2 = \"abc\"\n").unwrap();
+        assert_eq!(Some(&Expected::Number(42)), answers.get(Part::One));
+        assert_eq!("abc", answers.get(Part::Two).unwrap().to_string());
+        assert_eq!(Answers::default(), Answers::parse("# nothing yet").unwrap());
+        assert!(Answers::parse("part3 = 1").is_err());
+    }
+
+    #[test]
+    fn status_test() {
+        assert!(!Status::Pass.is_failure());
+        assert!(!Status::Missing.is_failure());
+        assert!(Status::Timeout.is_failure());
+        let fail = Status::Fail {
+            expected: "1".to_string(),
+            actual: "2".to_string(),
+        };
+        assert!(fail.is_failure());
+        assert_eq!("fail: expected 1, got 2", fail.to_string());
+    }
+}
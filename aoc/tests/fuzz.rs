@@ -0,0 +1,78 @@
+//! Feeds random input to the parser of every registered day and solves both parts
+//! of whatever parses, none of them may panic.
+
+use aoc::days::DAYS;
+use aoc_core::Part;
+use proptest::prelude::*;
+
+fn solve_all(text: &str) {
+    let lines = text.lines().map(str::to_owned).collect::<Vec<_>>();
+    for day in DAYS {
+        if let Ok(input) = day.solver.parse(&lines) {
+            for part in [Part::One, Part::Two] {
+                let _ = day.solver.solve(input.as_ref(), part);
+            }
+        }
+    }
+}
+
+/// A coordinate, often at the ends of `i64`.
+fn coordinate() -> impl Strategy<Value = i64> {
+    prop_oneof![-20i64..20, Just(i64::MIN), Just(i64::MAX), any::<i64>()]
+}
+
+/// Red tiles of day 9, one `x,y` per line.
+fn tiles() -> impl Strategy<Value = String> {
+    let tile = (coordinate(), coordinate()).prop_map(|(x, y)| format!("{},{}", x, y));
+    prop::collection::vec(tile, 0..12).prop_map(|lines| lines.join("\n"))
+}
+
+/// Machines of day 10, small enough for the searches, with light indices
+/// that can be past the lights and joltages that can be out of reach.
+fn machines() -> impl Strategy<Value = String> {
+    let lights = prop::collection::vec(prop_oneof![Just('.'), Just('#')], 0..4);
+    let button = prop::collection::vec(0usize..5, 0..3).prop_map(|lights| {
+        let lights = lights.iter().map(usize::to_string).collect::<Vec<_>>();
+        format!("({})", lights.join(","))
+    });
+    let buttons = prop::collection::vec(button, 0..4);
+    let joltage = prop::collection::vec(-1i16..3, 1..4);
+    let machine = (lights, buttons, joltage).prop_map(|(lights, buttons, joltage)| {
+        let joltage = joltage.iter().map(i16::to_string).collect::<Vec<_>>();
+        let lights = lights.into_iter().collect::<String>();
+        format!(
+            "[{}] {} {{{}}}",
+            lights,
+            buttons.join(" "),
+            joltage.join(",")
+        )
+    });
+    prop::collection::vec(machine, 0..3).prop_map(|lines| lines.join("\n"))
+}
+
+proptest! {
+    #[test]
+    fn random_bytes(bytes in prop::collection::vec(any::<u8>(), 0..256)) {
+        solve_all(&String::from_utf8_lossy(&bytes));
+    }
+
+    #[test]
+    fn puzzle_characters(text in r"[0-9LRS.@^#+*, \-\[\](){}\n]{0,256}") {
+        solve_all(&text);
+    }
+
+    #[test]
+    fn tile_lists(text in tiles()) {
+        solve_all(&text);
+    }
+
+    #[test]
+    fn machine_lists(text in machines()) {
+        solve_all(&text);
+    }
+
+    #[test]
+    fn worksheets(text in r"([0-9 ]{0,30}\n){0,25}[+* ]{0,30}") {
+        solve_all(&text);
+    }
+}
@@ -1,26 +1,55 @@
+use aoc_core::{Answer, ParseError, Part, Solution};
 use std::{fmt, str::FromStr};
 
-#[derive(thiserror::Error, Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
 pub enum Error {
-    #[error("Parse error")]
-    ParseError,
+    #[error("Parse error: {0}")]
+    ParseError(#[from] ParseError),
+    #[error("A dial has a positive size, got {0}")]
+    DialSize(i64),
+    #[error("Position {position} is not on a dial of size {size}")]
+    OffDial { position: i64, size: i64 },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-enum Command {
-    Left(i32),
-    Right(i32),
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Command {
+    Left(i64),
+    Right(i64),
+}
+
+impl Command {
+    /// Signed number of clicks, negative to the left.
+    pub fn offset(&self) -> i128 {
+        match *self {
+            Command::Left(n) => -(n as i128),
+            Command::Right(n) => n as i128,
+        }
+    }
+}
+
+fn parse_command(line: usize, s: &str) -> std::result::Result<Command, ParseError> {
+    let mut chars = s.chars();
+    let direction = chars.next();
+    let clicks = chars.as_str();
+    let number = || {
+        clicks
+            .parse::<i64>()
+            .map_err(|_| ParseError::at(line, s, clicks, "a number of clicks"))
+    };
+    match direction {
+        Some('L') => Ok(Command::Left(number()?)),
+        Some('R') => Ok(Command::Right(number()?)),
+        Some(c) => Err(ParseError::new(line, s, 0..c.len_utf8(), "`L` or `R`")),
+        None => Err(ParseError::at_end(line, s, "`L` or `R`")),
+    }
 }
 
 impl FromStr for Command {
     type Err = Error;
     fn from_str(s: &str) -> Result<Self> {
-        match (&s[..1], s[1..].parse::<i32>()) {
-            ("L", Ok(x)) => Ok(Command::Left(x)),
-            ("R", Ok(x)) => Ok(Command::Right(x)),
-            _ => Err(Error::ParseError),
-        }
+        Ok(parse_command(1, s)?)
     }
 }
 
@@ -33,58 +62,273 @@ impl fmt::Display for Command {
     }
 }
 
-pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<i32> {
-    let mut pos = 50;
-    let mut ans = 0;
-    let module = 100;
-    let commands = lines
+pub fn parse_input<S: AsRef<str>>(lines: &[S]) -> Result<Vec<Command>> {
+    lines
         .iter()
-        .map(|s| s.as_ref().parse::<Command>())
-        .collect::<Result<Vec<Command>>>()?;
-    for cmd in commands {
-        match cmd {
-            Command::Left(n) => {
-                pos = (pos - n) % module;
-            }
-            Command::Right(n) => {
-                pos = ((pos + n) % module + module) % module;
-            }
+        .enumerate()
+        .map(|(i, s)| Ok(parse_command(i + 1, s.as_ref())?))
+        .collect()
+}
+
+/// A safe dial numbered `0..size` with a pointer at `position`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Dial {
+    size: i64,
+    position: i64,
+}
+
+/// A single rotation of a [`Dial`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Step {
+    pub command: Command,
+    pub start: i64,
+    pub end: i64,
+    /// Clicks during the rotation that left the pointer at zero, the last one included.
+    /// A rotation by `i64::MAX` clicks passes zero up to 2^63 times, hence `i128`.
+    pub passes: i128,
+}
+
+impl Step {
+    /// Whether the rotation ends with the pointer at zero.
+    pub fn hits_zero(&self) -> bool {
+        self.end == 0
+    }
+}
+
+/// Zeros seen while following a sequence of commands.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Zeros {
+    /// Rotations that end at zero.
+    pub hits: i64,
+    /// Clicks that leave the pointer at zero.
+    pub passes: i128,
+}
+
+/// Number of multiples of `m` in `a..=b`.
+fn multiples(a: i128, b: i128, m: i128) -> i128 {
+    b.div_euclid(m) - (a - 1).div_euclid(m)
+}
+
+impl Dial {
+    /// Fails if `size` is not positive or `position` is not on the dial.
+    pub fn new(size: i64, position: i64) -> Result<Self> {
+        if size <= 0 {
+            return Err(Error::DialSize(size));
         }
-        if pos == 0 {
-            ans += 1;
+        if !(0..size).contains(&position) {
+            return Err(Error::OffDial { position, size });
         }
+        Ok(Self { size, position })
     }
-    Ok(ans)
-}
 
-pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<i32> {
-    let mut pos = 50;
-    let mut ans = 0;
-    let module = 100;
-    let commands = lines
-        .iter()
-        .map(|s| s.as_ref().parse::<Command>())
-        .collect::<Result<Vec<Command>>>()?;
-    for cmd in commands {
-        let next_pos = match cmd {
-            Command::Left(n) => pos - n,
-            Command::Right(n) => pos + n,
+    pub fn size(&self) -> i64 {
+        self.size
+    }
+
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    /// Rotates the dial by `command`.
+    /// The clicks at zero are the multiples of the size between the start, excluded, and the
+    /// unwrapped end, included, so even the largest rotations take constant time.
+    pub fn step(&mut self, command: Command) -> Step {
+        let start = self.position as i128;
+        let size = self.size as i128;
+        let next = start + command.offset();
+        let passes = if next >= start {
+            multiples(start + 1, next, size)
+        } else {
+            multiples(next, start - 1, size)
         };
-        if next_pos == 0 {
-            ans += 1;
-        } else if next_pos > 99 {
-            ans += next_pos / 100;
-        } else if next_pos < 0 {
-            ans += (next_pos / -100) + if pos == 0 { 0 } else { 1 };
+        self.position = next.rem_euclid(size) as i64;
+        Step {
+            command,
+            start: start as i64,
+            end: self.position,
+            passes,
         }
-        pos = (next_pos % module + module) % module;
     }
-    Ok(ans)
+
+    /// Rotates the dial by every command in turn.
+    pub fn steps<'a>(&'a mut self, commands: &'a [Command]) -> impl Iterator<Item = Step> + 'a {
+        commands.iter().map(|&command| self.step(command))
+    }
+
+    /// Rotates the dial by every command and counts the zeros.
+    pub fn count_zeros(&mut self, commands: &[Command]) -> Zeros {
+        self.steps(commands)
+            .fold(Zeros::default(), |zeros, step| Zeros {
+                hits: zeros.hits + step.hits_zero() as i64,
+                passes: zeros.passes + step.passes,
+            })
+    }
+
+    /// Shortest command that turns the pointer from `from` to `to`, `R0` if they are equal.
+    fn shortest_move(&self, from: i64, to: i64) -> Command {
+        let right = (to - from).rem_euclid(self.size);
+        let left = self.size - right;
+        if right <= left {
+            Command::Right(right)
+        } else {
+            Command::Left(left)
+        }
+    }
+
+    /// Position after following `commands` from the current one, the dial is not turned.
+    pub fn final_position(&self, commands: &[Command]) -> i64 {
+        let offset: i128 = commands.iter().map(Command::offset).sum();
+        (self.position as i128 + offset).rem_euclid(self.size as i128) as i64
+    }
+
+    /// Shortest list of commands that leaves the pointer where `commands` leave it.
+    pub fn compile(&self, commands: &[Command]) -> Vec<Command> {
+        let end = self.final_position(commands);
+        if end == self.position {
+            Vec::new()
+        } else {
+            vec![self.shortest_move(self.position, end)]
+        }
+    }
+
+    /// Shortest list of commands that leaves the pointer where `commands` leave it
+    /// and ends at zero as many times, so [`part1`] gives the same answer.
+    pub fn compile_hits(&self, commands: &[Command]) -> Vec<Command> {
+        let mut dial = *self;
+        let hits = dial.count_zeros(commands).hits;
+        if hits == 0 {
+            return self.compile(commands);
+        }
+        let end = self.final_position(commands);
+        let mut compiled = vec![self.shortest_move(self.position, 0)];
+        compiled.extend((1..hits).map(|_| Command::Right(0)));
+        if end != 0 {
+            compiled.push(self.shortest_move(0, end));
+        }
+        compiled
+    }
+}
+
+/// The dial of the puzzle: 100 positions, starting at 50.
+impl Default for Dial {
+    fn default() -> Self {
+        Self {
+            size: 100,
+            position: 50,
+        }
+    }
+}
+
+/// One command per line, in the format of the puzzle input.
+pub fn format_commands(commands: &[Command]) -> String {
+    commands
+        .iter()
+        .map(Command::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn part1(commands: &[Command]) -> i64 {
+    Dial::default().count_zeros(commands).hits
+}
+
+pub fn part2(commands: &[Command]) -> i128 {
+    Dial::default().count_zeros(commands).passes
+}
+
+/// A rotation of the dial with the zeros it adds to the count of a part.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct TraceLine {
+    pub step: Step,
+    /// Zeros counted for this rotation.
+    pub hits: i128,
+    /// Zeros counted so far, this rotation included.
+    pub total: i128,
+}
+
+/// Follows `commands` on `dial`, counting the zeros the way `part` does.
+pub fn trace(dial: &mut Dial, commands: &[Command], part: Part) -> Vec<TraceLine> {
+    let mut total = 0;
+    dial.steps(commands)
+        .map(|step| {
+            let hits = match part {
+                Part::One => step.hits_zero() as i128,
+                Part::Two => step.passes,
+            };
+            total += hits;
+            TraceLine { step, hits, total }
+        })
+        .collect()
+}
+
+pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<i64> {
+    Ok(part1(&parse_input(lines)?))
+}
+
+pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<i128> {
+    Ok(part2(&parse_input(lines)?))
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec<Command>;
+    type Error = Error;
+
+    fn parse<S: AsRef<str>>(&self, lines: &[S]) -> Result<Self::Input> {
+        parse_input(lines)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    /// Turns the dial one click at a time.
+    fn brute_force(size: i64, start: i64, command: Command) -> i128 {
+        let (delta, clicks) = match command {
+            Command::Left(n) => (-n.signum(), n.abs()),
+            Command::Right(n) => (n.signum(), n.abs()),
+        };
+        let mut position = start;
+        let mut passes = 0;
+        for _ in 0..clicks {
+            position = (position + delta).rem_euclid(size);
+            passes += (position == 0) as i128;
+        }
+        passes
+    }
+
+    fn small_command() -> impl Strategy<Value = Command> {
+        prop_oneof![
+            (-1000i64..1000).prop_map(Command::Left),
+            (-1000i64..1000).prop_map(Command::Right),
+        ]
+    }
+
+    /// Small commands, commands of any size and the extremes of `i64`.
+    fn command() -> impl Strategy<Value = Command> {
+        let extreme = prop_oneof![
+            Just(i64::MIN),
+            Just(i64::MIN + 1),
+            Just(i64::MAX),
+            any::<i64>()
+        ];
+        prop_oneof![
+            small_command(),
+            extreme.clone().prop_map(Command::Left),
+            extreme.prop_map(Command::Right),
+        ]
+    }
     const DATA: &str = r"L68
 L30
 R48
@@ -107,4 +351,122 @@ L82";
         let result = task2(&lines);
         assert_eq!(Ok(6), result);
     }
+    #[test]
+    fn dial_test() {
+        let lines = DATA.lines().collect::<Vec<_>>();
+        let commands = parse_input(&lines).unwrap();
+        let mut dial = Dial::new(10, 0).unwrap();
+        let step = dial.step(Command::Left(25));
+        assert_eq!((0, 5, 2), (step.start, step.end, step.passes));
+        assert_eq!(
+            Zeros { hits: 1, passes: 1 },
+            dial.count_zeros(&[Command::Right(5)])
+        );
+        let mut dial = Dial::default();
+        assert_eq!(Zeros { hits: 3, passes: 6 }, dial.count_zeros(&commands));
+        assert_eq!(32, dial.position());
+        assert_eq!(Err(Error::DialSize(0)), Dial::new(0, 0));
+        for position in [-1, 10] {
+            let error = Error::OffDial { position, size: 10 };
+            assert_eq!(Err(error), Dial::new(10, position));
+        }
+        assert_eq!(Ok(Dial::default()), Dial::new(100, 50));
+    }
+    #[test]
+    fn large_rotation_test() {
+        let mut dial = Dial::default();
+        let step = dial.step(Command::Right(i64::MAX));
+        assert_eq!((57, 92_233_720_368_547_758), (step.end, step.passes));
+        let step = dial.step(Command::Left(i64::MAX));
+        assert_eq!((50, 92_233_720_368_547_758), (step.end, step.passes));
+        assert_eq!(Ok(40_000_000), task2(&["L2000000000", "R2000000000"]));
+        let step = Dial::new(1, 0).unwrap().step(Command::Left(i64::MIN));
+        assert_eq!((0, 1 << 63), (step.end, step.passes));
+        let lines = vec!["R9223372036854775807"; 200];
+        assert_eq!(Ok((50 + 200 * i64::MAX as i128) / 100), task2(&lines));
+    }
+    proptest! {
+        #[test]
+        fn step_matches_brute_force(size in 1i64..200, start in 0i64..200, command in small_command()) {
+            let start = start % size;
+            let step = Dial::new(size, start).unwrap().step(command);
+            prop_assert_eq!(brute_force(size, start, command), step.passes);
+        }
+
+        #[test]
+        fn split_step_adds_passes(size in 1i64..=i64::MAX, start: i64, command in command()) {
+            // Two rotations the same way covering the same clicks pass zero as often.
+            let (half, rest) = match command {
+                Command::Left(n) => (Command::Left(n / 2), Command::Left(n - n / 2)),
+                Command::Right(n) => (Command::Right(n / 2), Command::Right(n - n / 2)),
+            };
+            let mut dial = Dial::new(size, start.rem_euclid(size)).unwrap();
+            let mut split = dial;
+            let step = dial.step(command);
+            let passes = split.step(half).passes + split.step(rest).passes;
+            prop_assert_eq!(step.passes, passes);
+            prop_assert_eq!(dial, split);
+            prop_assert!(step.passes <= command.offset().abs() / size as i128 + 1);
+        }
+    }
+    #[test]
+    fn compile_test() {
+        let lines = DATA.lines().collect::<Vec<_>>();
+        let commands = parse_input(&lines).unwrap();
+        let dial = Dial::default();
+        assert_eq!(vec![Command::Left(18)], dial.compile(&commands));
+        let compiled = dial.compile_hits(&commands);
+        assert_eq!("R50\nR0\nR0\nR32", format_commands(&compiled));
+        assert_eq!(3, part1(&compiled));
+        assert_eq!(
+            dial.final_position(&commands),
+            dial.final_position(&compiled)
+        );
+        assert!(dial
+            .compile(&[Command::Left(30), Command::Right(130)])
+            .is_empty());
+        assert_eq!(
+            vec![Command::Right(50)],
+            dial.compile_hits(&[Command::Left(50)])
+        );
+    }
+    proptest! {
+        #[test]
+        fn compile_keeps_hits(commands in prop::collection::vec(command(), 0..50)) {
+            let dial = Dial::new(100, 50).unwrap();
+            let compiled = dial.compile_hits(&commands);
+            prop_assert!(compiled.len() <= commands.len());
+            prop_assert_eq!(part1(&commands), part1(&compiled));
+            prop_assert_eq!(dial.final_position(&commands), dial.final_position(&compiled));
+        }
+    }
+    #[test]
+    fn trace_test() {
+        let lines = DATA.lines().collect::<Vec<_>>();
+        let commands = parse_input(&lines).unwrap();
+        let trace = trace(&mut Dial::default(), &commands, Part::Two);
+        assert_eq!(6, trace.last().unwrap().total);
+        let first = trace[0];
+        assert_eq!(
+            ("L68".to_string(), 50, 82, 1),
+            (
+                first.step.command.to_string(),
+                first.step.start,
+                first.step.end,
+                first.hits
+            )
+        );
+        let part1 = super::trace(&mut Dial::default(), &commands, Part::One);
+        let hits = part1.iter().map(|line| line.hits).collect::<Vec<_>>();
+        assert_eq!(vec![0, 0, 1, 0, 0, 1, 0, 1, 0, 0], hits);
+    }
+    #[test]
+    fn parse_error_test() {
+        let error = ParseError::new(2, "X5", 0..1, "`L` or `R`");
+        assert_eq!(Err(Error::ParseError(error)), task1(&["R10", "X5"]));
+        let error = ParseError::new(1, "L1x", 1..3, "a number of clicks");
+        assert_eq!(Err(Error::ParseError(error)), task2(&["L1x"]));
+        let error = ParseError::at_end(2, "", "`L` or `R`");
+        assert_eq!(Err(Error::ParseError(error)), task1(&["L1", ""]));
+    }
 }
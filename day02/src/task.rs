@@ -1,80 +1,216 @@
+use aoc_core::{Answer, ParseError, Solution};
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
+use std::iter;
+use std::ops::RangeInclusive;
+
 #[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
 pub enum Error {
-    #[error("Parse int error")]
-    ParseIntError(#[from] std::num::ParseIntError),
+    #[error("Parse error: {0}")]
+    ParseError(#[from] ParseError),
+    #[error("Reversed range: {0}")]
+    ReversedRange(#[source] ParseError),
+    #[error("Radix {0} is not in 2..=36")]
+    InvalidRadix(u32),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-fn half(x: u64) -> Result<u64> {
-    let s = format!("{}", x);
-    let n = s.len() / 2;
-    if n == 0 {
-        return Ok(1);
+/// An inclusive range of IDs.
+pub type IdRange = (BigUint, BigUint);
+
+fn digits(x: &BigUint, radix: u32) -> u32 {
+    x.to_radix_be(radix).len() as u32
+}
+
+/// IDs of `len` digits in `start..=end` made of a block repeated `k` times, sorted.
+fn repeated_with_len(
+    (start, end): &IdRange,
+    len: u32,
+    repeats: &RangeInclusive<u32>,
+    radix: u32,
+) -> Vec<BigUint> {
+    let radix = BigUint::from(radix);
+    let mut ids = Vec::new();
+    for k in (2.max(*repeats.start())..=len.min(*repeats.end())).filter(|k| len.is_multiple_of(*k))
+    {
+        let block = len / k;
+        // `x` repeated `k` times is `x * multiplier`, e.g. 1001001 for 3 blocks of 3 digits.
+        let multiplier = (radix.pow(len) - 1u32) / (radix.pow(block) - 1u32);
+        let low = radix
+            .pow(block - 1)
+            .max((start + &multiplier - 1u32) / &multiplier);
+        let high = (radix.pow(block) - 1u32).min(end / &multiplier);
+        let blocks = iter::successors(Some(low), |x| Some(x + 1u32)).take_while(|x| *x <= high);
+        ids.extend(blocks.map(|x| x * &multiplier));
     }
-    Ok(s[..n].parse()?)
+    ids.sort_unstable();
+    ids.dedup();
+    ids
 }
 
-fn half_one(x: u64) -> Result<u64> {
-    let s = format!("{}", x);
-    let n = s.len() / 2 + s.len() % 2;
-    Ok(s[..n].parse()?)
+/// Every ID in `range` made of a block of `radix` digits repeated `k` times for a `k` in `repeats`,
+/// in increasing order and without duplicates. `k` below 2 is ignored.
+/// The IDs are built from their blocks, so the work is proportional to their count.
+pub fn repeated_ids(
+    range: &IdRange,
+    repeats: RangeInclusive<u32>,
+    radix: u32,
+) -> impl Iterator<Item = BigUint> + '_ {
+    let (start, end) = range;
+    let last = if start <= end { digits(end, radix) } else { 0 };
+    let lengths = digits(start, radix)..=last;
+    lengths.flat_map(move |len| repeated_with_len(range, len, &repeats, radix))
 }
 
-fn has_substr(s: &str, sub: &str) -> bool {
-    s.len() == 0 || (s.starts_with(sub) && has_substr(&s[sub.len()..], sub))
+fn check_radix(radix: u32) -> Result<()> {
+    if (2..=36).contains(&radix) {
+        Ok(())
+    } else {
+        Err(Error::InvalidRadix(radix))
+    }
 }
 
-fn is_valid(s: &str) -> bool {
-    let n = s.len() / 2;
-    for i in 1..=n {
-        if s.len() % i != 0 {
-            continue;
-        }
-        if has_substr(&s[i..], &s[..i]) {
-            return true;
+/// Parses the comma separated lists of `start-end` ID ranges, written in base `radix` (2 to 36).
+/// The list can span several lines, and whitespace and empty items between commas are ignored.
+pub fn parse_ranges<S: AsRef<str>>(lines: &[S], radix: u32) -> Result<Vec<IdRange>> {
+    check_radix(radix)?;
+    let mut ranges = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let line = line.as_ref();
+        let parse_id = |token: &str| {
+            // `BigUint` also accepts `+` and `_`, only plain digits are IDs.
+            token
+                .chars()
+                .all(|c| c.is_digit(radix))
+                .then(|| BigUint::parse_bytes(token.as_bytes(), radix))
+                .flatten()
+                .ok_or_else(|| ParseError::at(i + 1, line, token, "an ID number"))
+        };
+        for item in line.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let (start, end) = item
+                .split_once('-')
+                .ok_or_else(|| ParseError::at(i + 1, line, item, "a `start-end` range"))?;
+            let (start, end) = (start.trim(), end.trim());
+            let (start_id, end_id) = (parse_id(start)?, parse_id(end)?);
+            if end_id < start_id {
+                let expected = format!("an ID not below `{}`", start);
+                return Err(Error::ReversedRange(ParseError::at(
+                    i + 1,
+                    line,
+                    end,
+                    expected,
+                )));
+            }
+            ranges.push((start_id, end_id));
         }
     }
-    return false;
+    if ranges.is_empty() {
+        let last = lines.last().map_or("", |l| l.as_ref());
+        let line = lines.len().max(1);
+        return Err(ParseError::at_end(line, last, "a `start-end` range").into());
+    }
+    Ok(ranges)
 }
 
-pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
-    let mut ans = 0u64;
-    let ranges = lines[0]
-        .as_ref()
-        .split(',')
-        .map(|s| s.split_once('-').unwrap());
+/// Sorts the ranges and joins the ones that overlap or touch, so that every ID is in one range.
+pub fn merge_ranges(mut ranges: Vec<IdRange>) -> Vec<IdRange> {
+    ranges.sort_unstable();
+    let mut merged: Vec<IdRange> = Vec::with_capacity(ranges.len());
     for (start, end) in ranges {
-        let start_num = start.parse::<u64>()?;
-        let end_num = end.parse::<u64>()?;
-        let start_x = half(start_num)?;
-        let end_x = half_one(end_num)?;
-        for x in start_x..=end_x {
-            let prop = format!("{}{}", x, x).parse::<u64>()?;
-            if prop >= start_num && prop <= end_num {
-                ans += prop;
+        match merged.last_mut() {
+            Some((_, last_end)) if start <= &*last_end + 1u32 => {
+                if end > *last_end {
+                    *last_end = end;
+                }
             }
+            _ => merged.push((start, end)),
         }
     }
-    Ok(ans)
+    merged
 }
 
-pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
-    let mut ans = 0u64;
-    let ranges = lines[0]
-        .as_ref()
-        .split(',')
-        .map(|s| s.split_once('-').unwrap());
-    for (start, end) in ranges {
-        let start_num = start.parse::<u64>()?;
-        let end_num = end.parse::<u64>()?;
-        for x in start_num..=end_num {
-            if is_valid(&format!("{}", x)) {
-                ans += x;
-            }
+/// Parses the comma separated list of decimal `start-end` ID ranges.
+pub fn parse_input<S: AsRef<str>>(lines: &[S]) -> Result<Vec<IdRange>> {
+    parse_ranges(lines, 10)
+}
+
+/// Sums the IDs made of a block repeated twice.
+pub fn part1(ranges: &[IdRange], radix: u32) -> BigUint {
+    ranges
+        .iter()
+        .flat_map(|range| repeated_ids(range, 2..=2, radix))
+        .sum()
+}
+
+/// Sums the IDs made of a block repeated at least twice.
+pub fn part2(ranges: &[IdRange], radix: u32) -> BigUint {
+    ranges
+        .iter()
+        .flat_map(|range| repeated_ids(range, 2..=u32::MAX, radix))
+        .sum()
+}
+
+pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<BigUint> {
+    Ok(part1(&parse_input(lines)?, 10))
+}
+
+pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<BigUint> {
+    Ok(part2(&parse_input(lines)?, 10))
+}
+
+/// Sums that do not fit in an `i128` are given as text.
+fn answer(sum: BigUint) -> Answer {
+    match sum.to_i128() {
+        Some(n) => Answer::Number(n),
+        None => Answer::Text(sum.to_string()),
+    }
+}
+
+/// Solution of day 2, the IDs are written in base `radix`.
+/// With `merge` overlapping ranges are joined, so an ID in several of them is counted once.
+pub struct Day02 {
+    pub radix: u32,
+    pub merge: bool,
+}
+
+impl Day02 {
+    /// Fails if `radix` is not in 2..=36.
+    pub fn new(radix: u32, merge: bool) -> Result<Self> {
+        check_radix(radix)?;
+        Ok(Self { radix, merge })
+    }
+}
+
+impl Default for Day02 {
+    fn default() -> Self {
+        Self {
+            radix: 10,
+            merge: false,
         }
     }
-    Ok(ans)
+}
+
+impl Solution for Day02 {
+    type Input = Vec<IdRange>;
+    type Error = Error;
+
+    fn parse<S: AsRef<str>>(&self, lines: &[S]) -> Result<Self::Input> {
+        let ranges = parse_ranges(lines, self.radix)?;
+        Ok(if self.merge {
+            merge_ranges(ranges)
+        } else {
+            ranges
+        })
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        Ok(answer(part1(input, self.radix)))
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        Ok(answer(part2(input, self.radix)))
+    }
 }
 
 #[cfg(test)]
@@ -82,21 +218,108 @@ mod tests {
     use super::*;
     const DATA: &str =
 "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
+    fn big(x: u64) -> BigUint {
+        BigUint::from(x)
+    }
     #[test]
     fn task1_test() {
         let lines = DATA.lines().collect::<Vec<_>>();
         let result = task1(&lines);
-        assert_eq!(Ok(1227775554), result);
+        assert_eq!(Ok(big(1227775554)), result);
     }
     #[test]
     fn task2_test() {
         let lines = DATA.lines().collect::<Vec<_>>();
         let result = task2(&lines);
-        assert_eq!(Ok(4174379265), result);
+        assert_eq!(Ok(big(4174379265)), result);
     }
     #[test]
     fn task1_test21() {
         let lines = vec!["1-21"];
-        assert_eq!(Ok(11), task1(&lines));
+        assert_eq!(Ok(big(11)), task1(&lines));
+    }
+    fn has_substr(s: &str, sub: &str) -> bool {
+        s.is_empty() || (s.starts_with(sub) && has_substr(&s[sub.len()..], sub))
+    }
+
+    /// Tries every block length of `x`.
+    fn is_repeated(x: &BigUint, repeats: &RangeInclusive<u32>, radix: u32) -> bool {
+        let s = x.to_str_radix(radix);
+        (1..s.len())
+            .filter(|&i| s.len().is_multiple_of(i) && repeats.contains(&((s.len() / i) as u32)))
+            .any(|i| has_substr(&s[i..], &s[..i]))
+    }
+
+    #[test]
+    fn repeated_ids_test() {
+        for (repeats, radix) in [
+            (2..=2, 10),
+            (3..=3, 10),
+            (2..=u32::MAX, 10),
+            (2..=u32::MAX, 2),
+        ] {
+            let expected = (1..=100_000)
+                .map(big)
+                .filter(|x| is_repeated(x, &repeats, radix))
+                .collect::<Vec<_>>();
+            let ids = repeated_ids(&(big(1), big(100_000)), repeats, radix).collect::<Vec<_>>();
+            assert_eq!(expected, ids);
+        }
+        let range = (big(1_000_000), big(1_111_111));
+        let ids = repeated_ids(&range, 2..=u32::MAX, 10).collect::<Vec<_>>();
+        assert_eq!(vec![big(1_111_111)], ids);
+        assert_eq!(0, repeated_ids(&(big(30), big(10)), 2..=2, 10).count());
+    }
+    #[test]
+    fn radix_test() {
+        let ranges = parse_ranges(&["a-FF"], 16).unwrap();
+        assert_eq!(vec![(big(10), big(255))], ranges);
+        let expected = (1..=15u64).map(|d| d * 0x11).sum::<u64>();
+        assert_eq!(big(expected), part1(&ranges, 16));
+        let error = ParseError::new(1, "10-1g", 3..5, "an ID number");
+        assert_eq!(Err(Error::ParseError(error)), parse_ranges(&["10-1g"], 16));
+        let ones = "1".repeat(40);
+        let input = Day02::default()
+            .parse(&[format!("{}-{}", ones, ones)])
+            .unwrap();
+        assert_eq!(Ok(Answer::Text(ones)), Day02::default().part2(&input));
+        assert_eq!(Err(Error::InvalidRadix(37)), parse_ranges(&["1-2"], 37));
+        assert_eq!(Some(Error::InvalidRadix(1)), Day02::new(1, false).err());
+        let solution = Day02::new(2, false).unwrap();
+        let input = solution.parse(&["1-11"]).unwrap();
+        assert_eq!(Ok(Answer::Number(0b11)), solution.part1(&input));
+    }
+    #[test]
+    fn parse_error_test() {
+        let error = ParseError::new(1, "11-22,95-1x5", 9..12, "an ID number");
+        assert_eq!(Err(Error::ParseError(error)), task1(&["11-22,95-1x5"]));
+        let error = ParseError::new(1, "11-22,95", 6..8, "a `start-end` range");
+        assert_eq!(Err(Error::ParseError(error)), task2(&["11-22,95"]));
+        let error = ParseError::at_end(1, "", "a `start-end` range");
+        assert_eq!(Err(Error::ParseError(error)), task1::<&str>(&[]));
+        let error = ParseError::new(2, "1-2, 30-10", 8..10, "an ID not below `30`");
+        assert_eq!(
+            Err(Error::ReversedRange(error)),
+            task1(&["5-6", "1-2, 30-10"])
+        );
+    }
+    #[test]
+    fn range_list_test() {
+        let lines = ["11-22,95-115,", "", " 998 - 1012 ,1188511880-1188511890"];
+        let ranges = parse_input(&lines).unwrap();
+        assert_eq!((big(998), big(1012)), ranges[2]);
+        assert_eq!(big(11 + 22 + 99 + 1010 + 1188511885), part1(&ranges, 10));
+        let overlapping = ["11-22,15-33,34-40", "100-200,150-160"];
+        let solution = Day02 {
+            radix: 10,
+            merge: true,
+        };
+        let ranges = solution.parse(&overlapping).unwrap();
+        assert_eq!(vec![(big(11), big(40)), (big(100), big(200))], ranges);
+        assert_eq!(
+            Ok(Answer::Number(11 + 22 + 33 + 111)),
+            solution.part2(&ranges)
+        );
+        assert_eq!(big(11 + 22 + 22 + 33 + 111), task2(&overlapping).unwrap());
     }
 }
@@ -1,64 +1,156 @@
-#[derive(thiserror::Error, Debug, Clone, Copy, Eq, PartialEq)]
+use aoc_core::{Answer, ParseError, Solution};
+
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
 pub enum Error {
-    #[error("Parse error")]
-    ParseError,
+    #[error("Parse error: {0}")]
+    ParseError(#[from] ParseError),
+    #[error("A bank of {len} batteries has no {k} batteries to turn on")]
+    TooShort { len: usize, k: usize },
+    #[error("A joltage of {0} digits or their sum does not fit in 64 bits")]
+    Overflow(usize),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-fn max_pair(s: &str) -> i32 {
-    let (m1, m2) = s.bytes().fold((b'0', b'0'), |(m1, m2), b| {
-        if m2 > m1 {
-            (m2, b)
-        } else {
-            if b > m2 {
-                (m1, b)
-            } else {
-                (m1, m2)
-            }
-        }
-    });
-    (m1 - b'0') as i32 * 10 + (m2 - b'0') as i32
+/// Batteries picked from a bank, in bank order.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Selection {
+    /// Positions of the batteries in the bank, increasing.
+    pub indices: Vec<usize>,
+    /// Joltage digits of the batteries.
+    pub digits: Vec<u8>,
 }
 
-fn reorder(mut v: Vec<u8>) -> std::result::Result<Vec<u8>, Vec<u8>> {
-    for i in 1..v.len() {
-        if v[i - 1] < v[i] {
-            v.remove(i - 1);
-            return Ok(v);
+impl Selection {
+    fn new(bank: &[u8], indices: Vec<usize>) -> Self {
+        Self {
+            digits: indices.iter().map(|&i| bank[i]).collect(),
+            indices,
         }
     }
-    return Err(v);
+
+    /// The joltage formed by the digits, `None` if it does not fit in a `u64`.
+    pub fn value(&self) -> Option<u64> {
+        self.digits
+            .iter()
+            .try_fold(0u64, |acc, &d| acc.checked_mul(10)?.checked_add(d as u64))
+    }
 }
 
-fn max_twelve(s: &str) -> u64 {
-    let bytes = s.as_bytes();
-    let mut twelve = bytes[..12].to_vec();
-    for &b in bytes.iter().skip(12) {
-        twelve = match reorder(twelve) {
-            Ok(mut changed) => {
-                changed.push(b);
-                changed
-            }
-            Err(mut changed) => {
-                if let Some(x) = changed.last_mut() {
-                    *x = b.max(*x);
-                }
-                changed
+/// Picks `k` digits of `bank` in order, preferring `d` over `top` when `replaces(top, d)`.
+/// Keeps a stack of picked digits and drops the top whenever a preferred digit comes
+/// and enough digits are left to still pick `k`, so it runs in linear time.
+/// Among equal digits the earliest ones are picked.
+fn greedy_subsequence(
+    bank: &[u8],
+    k: usize,
+    replaces: impl Fn(u8, u8) -> bool,
+) -> Result<Selection> {
+    let n = bank.len();
+    if n < k {
+        return Err(Error::TooShort { len: n, k });
+    }
+    let mut stack: Vec<usize> = Vec::with_capacity(k);
+    for (i, &digit) in bank.iter().enumerate() {
+        while let Some(&top) = stack.last() {
+            if replaces(bank[top], digit) && stack.len() - 1 + (n - i) >= k {
+                stack.pop();
+            } else {
+                break;
             }
-        };
+        }
+        if stack.len() < k {
+            stack.push(i);
+        }
     }
-    twelve
-        .into_iter()
-        .fold(0u64, |acc, b| acc * 10 + (b - b'0') as u64)
+    Ok(Selection::new(bank, stack))
+}
+
+/// Largest number formed by `k` digits of `bank` kept in order.
+pub fn max_subsequence(bank: &[u8], k: usize) -> Result<Selection> {
+    greedy_subsequence(bank, k, |top, digit| top < digit)
+}
+
+/// Smallest number formed by `k` digits of `bank` kept in order, leading zeros included.
+pub fn min_subsequence(bank: &[u8], k: usize) -> Result<Selection> {
+    greedy_subsequence(bank, k, |top, digit| top > digit)
+}
+
+/// Earliest positions of `bank` that spell the digits of `target` in order,
+/// `None` if `target` is not a subsequence of `bank`.
+pub fn find_target(bank: &[u8], target: &[u8]) -> Option<Selection> {
+    let mut positions = bank.iter().enumerate();
+    let indices = target
+        .iter()
+        .map(|&t| positions.find(|&(_, &d)| d == t).map(|(i, _)| i))
+        .collect::<Option<Vec<_>>>()?;
+    Some(Selection::new(bank, indices))
+}
+
+/// Sums the largest `k` digit joltage of every bank, an error if a joltage or the sum
+/// does not fit in a `u64`.
+pub fn total_joltage(banks: &[Vec<u8>], k: usize) -> Result<u64> {
+    banks.iter().try_fold(0u64, |sum, bank| {
+        let value = max_subsequence(bank, k)?
+            .value()
+            .ok_or(Error::Overflow(k))?;
+        sum.checked_add(value).ok_or(Error::Overflow(k))
+    })
+}
+
+/// Parses a bank of joltage digits into their values.
+pub fn parse_bank(line: usize, text: &str) -> std::result::Result<Vec<u8>, ParseError> {
+    text.char_indices()
+        .map(|(j, c)| {
+            c.to_digit(10)
+                .map(|d| d as u8)
+                .ok_or_else(|| ParseError::new(line, text, j..j + c.len_utf8(), "a joltage digit"))
+        })
+        .collect()
+}
+
+/// Parses the battery banks, one line of joltage digits each.
+pub fn parse_input<S: AsRef<str>>(lines: &[S]) -> Result<Vec<Vec<u8>>> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| Ok(parse_bank(i + 1, line.as_ref())?))
+        .collect()
 }
 
-pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<i32> {
-    Ok(lines.iter().map(|line| max_pair(line.as_ref())).sum())
+pub fn part1(banks: &[Vec<u8>]) -> Result<u64> {
+    total_joltage(banks, 2)
+}
+
+pub fn part2(banks: &[Vec<u8>]) -> Result<u64> {
+    total_joltage(banks, 12)
+}
+
+pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
+    part1(&parse_input(lines)?)
 }
 
 pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
-    Ok(lines.iter().map(|line| max_twelve(line.as_ref())).sum())
+    part2(&parse_input(lines)?)
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Vec<Vec<u8>>;
+    type Error = Error;
+
+    fn parse<S: AsRef<str>>(&self, lines: &[S]) -> Result<Self::Input> {
+        parse_input(lines)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        part2(input).map(Answer::from)
+    }
 }
 
 #[cfg(test)]
@@ -80,4 +172,67 @@ mod tests {
         let result = task2(&lines);
         assert_eq!(Ok(3121910778619), result);
     }
+    #[test]
+    fn max_subsequence_test() {
+        let bank = parse_bank(1, "818181911112111").unwrap();
+        let selection = max_subsequence(&bank, 4).unwrap();
+        assert_eq!(vec![6, 11, 12, 13], selection.indices);
+        assert_eq!(Some(9211), selection.value());
+        let error = Error::TooShort { len: 3, k: 12 };
+        assert_eq!(Err(error.clone()), max_subsequence(&[1, 2, 3], 12));
+        assert_eq!(Err(error), task2(&["987654321111111", "123"]));
+        let long = vec![9; 20];
+        assert_eq!(Err(Error::Overflow(20)), total_joltage(&[long], 20));
+        let banks = [vec![9; 19], vec![9; 19]];
+        assert_eq!(Err(Error::Overflow(19)), total_joltage(&banks, 19));
+        assert_eq!(
+            Ok(9_999_999_999_999_999_999),
+            total_joltage(&banks[..1], 19)
+        );
+    }
+    #[test]
+    fn brute_force_test() {
+        // Every bank of up to 6 digits from 1 to 3, against every choice of positions.
+        let banks = (0..=6u32).flat_map(|len| {
+            (0..3usize.pow(len)).map(move |code| {
+                (0..len)
+                    .map(|i| 1 + (code / 3usize.pow(i) % 3) as u8)
+                    .collect::<Vec<_>>()
+            })
+        });
+        for bank in banks {
+            for k in 0..=bank.len() {
+                let choices = (0..1u32 << bank.len())
+                    .filter(|mask| mask.count_ones() as usize == k)
+                    .map(|mask| {
+                        let picked = (0..bank.len()).filter(|i| mask >> i & 1 == 1);
+                        picked.map(|i| bank[i]).collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+                let max = max_subsequence(&bank, k).unwrap();
+                assert_eq!(choices.iter().max(), Some(&max.digits), "{:?} {}", bank, k);
+                let min = min_subsequence(&bank, k).unwrap();
+                assert_eq!(choices.iter().min(), Some(&min.digits), "{:?} {}", bank, k);
+                let target = find_target(&bank, &max.digits).unwrap();
+                assert_eq!(max.digits, target.digits);
+            }
+        }
+    }
+    #[test]
+    fn min_and_target_test() {
+        let bank = parse_bank(1, "818181911112111").unwrap();
+        let selection = min_subsequence(&bank, 4).unwrap();
+        let expected = (Some(1111), vec![1, 3, 5, 7]);
+        assert_eq!(expected, (selection.value(), selection.indices));
+        let target = parse_bank(1, "8912").unwrap();
+        let selection = find_target(&bank, &target).unwrap();
+        assert_eq!(vec![0, 6, 7, 11], selection.indices);
+        assert_eq!(None, find_target(&bank, &[9, 9]));
+        assert_eq!(Some(vec![]), find_target(&bank, &[]).map(|s| s.indices));
+    }
+    #[test]
+    fn parse_error_test() {
+        let error = ParseError::new(2, "12a4", 2..3, "a joltage digit");
+        assert_eq!(Err(Error::ParseError(error)), task1(&["987", "12a4"]));
+    }
 }
@@ -0,0 +1,5 @@
+mod stream;
+mod task;
+
+pub use stream::*;
+pub use task::*;
@@ -0,0 +1,305 @@
+use crate::task::{parse_cell, Cell, Edges, Error, Neighbourhood, Result, Rules, CELLS};
+use aoc_core::{Grid, Pos};
+use std::collections::VecDeque;
+use std::mem;
+
+/// Parses the row at index `i` of the map, `width` is the length of the first row.
+fn parse_row(i: usize, line: &str, width: Option<usize>) -> Result<Vec<Cell>> {
+    Ok(Grid::parse_row(i + 1, line, width, parse_cell, CELLS)?)
+}
+
+/// Part 1 on a map read one row at a time. Only the rows the neighbourhood reaches
+/// are kept, three for the puzzle rules, so the map does not have to fit in memory.
+/// Rows cannot wrap around without the whole map, [`Edges::Wrap`] is an error.
+pub fn stream_part1<S: AsRef<str>>(
+    lines: impl IntoIterator<Item = S>,
+    rules: &Rules,
+) -> Result<u64> {
+    if rules.edges == Edges::Wrap {
+        return Err(Error::StreamedWrap);
+    }
+    let neighbourhood = Neighbourhood::new(rules);
+    let reach = neighbourhood
+        .offsets
+        .iter()
+        .map(|&(di, _)| di.unsigned_abs())
+        .max()
+        .unwrap_or(0);
+    // Rows from `first` on, the rows before `next` are already checked.
+    let mut window: VecDeque<Vec<Cell>> = VecDeque::new();
+    let (mut first, mut next, mut width) = (0, 0, None);
+    let mut count = 0;
+    let mut check = |window: &VecDeque<Vec<Cell>>, first: usize, i: usize| {
+        let row = &window[i - first];
+        let size = (usize::MAX, row.len());
+        let accessible = (0..row.len()).filter(|&j| {
+            row[j] == Cell::Roll
+                && neighbourhood
+                    .neighbours(size, (i, j))
+                    .filter(|&(r, k)| {
+                        r.checked_sub(first)
+                            .and_then(|r| window.get(r))
+                            .is_some_and(|row| row[k] == Cell::Roll)
+                    })
+                    .count()
+                    < rules.threshold
+        });
+        count += accessible.count() as u64;
+    };
+    for (i, line) in lines.into_iter().enumerate() {
+        let row = parse_row(i, line.as_ref(), width)?;
+        width.get_or_insert(row.len());
+        window.push_back(row);
+        if i >= reach {
+            check(&window, first, next);
+            next += 1;
+            while first + reach < next {
+                window.pop_front();
+                first += 1;
+            }
+        }
+    }
+    while next < first + window.len() {
+        check(&window, first, next);
+        next += 1;
+    }
+    Ok(count)
+}
+
+/// Positions of the rolls of a warehouse, for maps that are mostly empty.
+/// The columns of the rolls are stored row by row and sorted, so a roll is found
+/// by binary search in its row and takes 4 bytes.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Rolls {
+    height: usize,
+    width: usize,
+    /// Index in `columns` of the first roll of every row, and the number of rolls.
+    row_starts: Vec<usize>,
+    columns: Vec<u32>,
+}
+
+impl Default for Rolls {
+    fn default() -> Self {
+        Self {
+            height: 0,
+            width: 0,
+            row_starts: vec![0],
+            columns: Vec::new(),
+        }
+    }
+}
+
+impl Rolls {
+    pub fn from_grid(grid: &Grid<Cell>) -> Result<Self> {
+        let mut rolls = Self::with_width(grid.width())?;
+        for row in grid.rows() {
+            rolls.push_row(row)?;
+        }
+        Ok(rolls)
+    }
+
+    fn with_width(width: usize) -> Result<Self> {
+        if width > 1 << 32 {
+            return Err(Error::SparseTooLarge);
+        }
+        Ok(Self {
+            width,
+            ..Self::default()
+        })
+    }
+
+    fn push_row(&mut self, row: &[Cell]) -> Result<()> {
+        if self.height == 1 << 32 {
+            return Err(Error::SparseTooLarge);
+        }
+        let rolls = (0..row.len()).filter(|&j| row[j] == Cell::Roll);
+        self.columns.extend(rolls.map(|j| j as u32));
+        self.row_starts.push(self.columns.len());
+        self.height += 1;
+        Ok(())
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of rolls.
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        self.index(pos).is_some()
+    }
+
+    /// Index of the roll at `pos` in `columns`.
+    fn index(&self, (i, j): Pos) -> Option<usize> {
+        if i >= self.height || j >= self.width {
+            return None;
+        }
+        let start = self.row_starts[i];
+        let row = &self.columns[start..self.row_starts[i + 1]];
+        row.binary_search(&(j as u32)).ok().map(|k| start + k)
+    }
+
+    /// The positions of the rolls, in the order of their indices.
+    fn positions(&self) -> impl Iterator<Item = Pos> + '_ {
+        self.row_starts
+            .windows(2)
+            .enumerate()
+            .flat_map(move |(i, w)| {
+                self.columns[w[0]..w[1]]
+                    .iter()
+                    .map(move |&j| (i, j as usize))
+            })
+    }
+}
+
+/// Parses the map one row at a time, keeping only the positions of the rolls.
+pub fn parse_sparse<S: AsRef<str>>(lines: impl IntoIterator<Item = S>) -> Result<Rolls> {
+    let mut rolls = Rolls::default();
+    for (i, line) in lines.into_iter().enumerate() {
+        let row = parse_row(i, line.as_ref(), (i > 0).then_some(rolls.width))?;
+        if i == 0 {
+            rolls = Rolls::with_width(row.len())?;
+        }
+        rolls.push_row(&row)?;
+    }
+    Ok(rolls)
+}
+
+/// Count of a removed roll.
+const REMOVED: u32 = u32::MAX;
+
+/// Part 2 on the positions of the rolls, with the worklist of [`crate::Removal`].
+/// The counts are indexed like the rolls and also tell which rolls are left,
+/// so nothing else is stored per roll.
+pub fn sparse_part2(rolls: &Rolls, rules: &Rules) -> u64 {
+    let neighbourhood = Neighbourhood::new(rules);
+    let watchers = neighbourhood.reversed();
+    let size = (rolls.height, rolls.width);
+    let mut counts = rolls
+        .positions()
+        .map(|pos| {
+            neighbourhood
+                .neighbours(size, pos)
+                .filter(|&p| rolls.contains(p))
+                .count() as u32
+        })
+        .collect::<Vec<_>>();
+    let mut next = rolls
+        .positions()
+        .zip(&counts)
+        .filter(|&(_, &count)| (count as usize) < rules.threshold)
+        .map(|(pos, _)| pos)
+        .collect::<Vec<_>>();
+    let mut removed = 0;
+    while !next.is_empty() {
+        for &pos in &next {
+            counts[rolls.index(pos).expect("`pos` is a roll")] = REMOVED;
+        }
+        removed += next.len() as u64;
+        for pos in mem::take(&mut next) {
+            for p in watchers.neighbours(size, pos) {
+                let Some(k) = rolls.index(p) else {
+                    continue;
+                };
+                let count = &mut counts[k];
+                if *count != REMOVED {
+                    // Counts only go down, so a roll crosses the threshold once.
+                    if *count as usize == rules.threshold {
+                        next.push(p);
+                    }
+                    *count -= 1;
+                }
+            }
+        }
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{parse_input, part1, part2, Shape};
+    use aoc_core::ParseError;
+    const DATA: &str = r"..@@.@@@@.
+@@@.@.@.@@
+@@@@@.@.@@
+@.@@@@..@.
+@@.@@@@.@@
+.@@@@@@@.@
+.@.@.@.@@@
+@.@@@.@@@@
+.@@@@@@@@.
+@.@.@@@.@.";
+    #[test]
+    fn stream_test() {
+        let lines = DATA.lines().collect::<Vec<_>>();
+        let grid = parse_input(&lines).unwrap();
+        let rules = [
+            Rules::PUZZLE,
+            Rules {
+                shape: Shape::VonNeumann,
+                radius: 3,
+                threshold: 9,
+                ..Rules::PUZZLE
+            },
+            Rules {
+                shape: Shape::Custom(vec![(2, 1), (-1, 0), (0, 0)]),
+                threshold: 2,
+                ..Rules::PUZZLE
+            },
+            Rules {
+                shape: Shape::Custom(vec![]),
+                ..Rules::PUZZLE
+            },
+        ];
+        for rules in &rules {
+            let expected = part1(&grid, rules) as u64;
+            assert_eq!(Ok(expected), stream_part1(&lines, rules), "{:?}", rules);
+            let lines = &lines[..3];
+            let expected = part1(&parse_input(lines).unwrap(), rules) as u64;
+            assert_eq!(Ok(expected), stream_part1(lines, rules), "{:?}", rules);
+        }
+        assert_eq!(Ok(13), stream_part1(DATA.lines(), &Rules::PUZZLE));
+        assert_eq!(Ok(0), stream_part1(Vec::<&str>::new(), &Rules::PUZZLE));
+        let wrap = Rules {
+            edges: Edges::Wrap,
+            ..Rules::PUZZLE
+        };
+        assert_eq!(Err(Error::StreamedWrap), stream_part1(&lines, &wrap));
+    }
+    #[test]
+    fn sparse_test() {
+        let grid = parse_input(&DATA.lines().collect::<Vec<_>>()).unwrap();
+        let rolls = parse_sparse(DATA.lines()).unwrap();
+        assert_eq!(Ok(&rolls), Rolls::from_grid(&grid).as_ref());
+        assert_eq!((10, 10, 71), (rolls.height(), rolls.width(), rolls.len()));
+        assert!(rolls.contains((0, 2)) && !rolls.contains((0, 1)) && !rolls.contains((10, 2)));
+        assert_eq!(43, sparse_part2(&rolls, &Rules::PUZZLE));
+        let wrap = Rules {
+            shape: Shape::VonNeumann,
+            radius: 2,
+            threshold: 6,
+            edges: Edges::Wrap,
+        };
+        assert_eq!(part2(&grid, &wrap) as u64, sparse_part2(&rolls, &wrap));
+    }
+    #[test]
+    fn parse_error_test() {
+        let error = ParseError::new(3, ".#.", 1..2, "`.` or `@`");
+        let result = stream_part1(["@@@", "...", ".#."], &Rules::PUZZLE);
+        assert_eq!(Err(Error::ParseError(error)), result);
+        let error = ParseError::whole_line(2, "..", "a row of 3 cells");
+        assert_eq!(Err(Error::ParseError(error)), parse_sparse(["@@@", ".."]));
+    }
+}
@@ -1,107 +1,320 @@
-use std::mem;
+use aoc_core::{Answer, Grid, ParseError, Pos, Solution};
+use std::{fmt, mem};
 
-#[derive(thiserror::Error, Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
 pub enum Error {
-    #[error("Parse error")]
-    ParseError,
+    #[error("Parse error: {0}")]
+    ParseError(#[from] ParseError),
+    #[error("Rows cannot wrap around when the map is streamed")]
+    StreamedWrap,
+    #[error("A sparse map has at most 2^32 rows and columns")]
+    SparseTooLarge,
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
-enum Cell {
+pub enum Cell {
     Empty,
     Roll,
 }
 
-fn parse_cell(c: char) -> Result<Cell> {
+/// Description of the valid cells for parse errors.
+pub(crate) const CELLS: &str = "`.` or `@`";
+
+pub(crate) fn parse_cell(c: char) -> Option<Cell> {
     match c {
-        '.' => Ok(Cell::Empty),
-        '@' => Ok(Cell::Roll),
-        _ => Err(Error::ParseError),
-    }
-}
-
-fn count_rolls(grid: &Vec<Vec<Cell>>, i: usize, j: usize) -> u8 {
-    let start_i = i.checked_sub(1).unwrap_or(0);
-    let end_i = grid.len().min(i + 2);
-    let start_j = j.checked_sub(1).unwrap_or(0);
-    let end_j = grid[0].len().min(j + 2);
-    let mut result = 0;
-    for ii in start_i..end_i {
-        for jj in start_j..end_j {
-            if ii == i && jj == j {
-                continue;
-            }
-            if grid[ii][jj] == Cell::Roll {
-                result += 1;
-            }
+        '.' => Some(Cell::Empty),
+        '@' => Some(Cell::Roll),
+        _ => None,
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cell::Empty => write!(f, "."),
+            Cell::Roll => write!(f, "@"),
         }
     }
-    result
 }
 
-pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<i32> {
-    let grid = lines
-        .into_iter()
-        .map(|line| {
-            line.as_ref()
-                .chars()
-                .map(parse_cell)
-                .collect::<Result<Vec<Cell>>>()
+/// Cells that count as around a roll.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Shape {
+    /// Cells at most `radius` steps away along rows and columns.
+    VonNeumann,
+    /// Cells in the square of side `2 * radius + 1` centred on the roll.
+    Moore,
+    /// Cells at the given `(row, column)` offsets, the radius is not used.
+    Custom(Vec<(isize, isize)>),
+}
+
+/// What lies past the edges of the warehouse.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Edges {
+    /// Nothing, cells outside of the warehouse are never counted.
+    Bounded,
+    /// The other side, rows and columns wrap around.
+    Wrap,
+}
+
+/// When a forklift can reach a roll: fewer than `threshold` rolls in its neighbourhood.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Rules {
+    pub shape: Shape,
+    pub radius: usize,
+    pub threshold: usize,
+    pub edges: Edges,
+}
+
+impl Rules {
+    /// The rules of the puzzle, fewer than 4 rolls among the 8 adjacent cells.
+    pub const PUZZLE: Rules = Rules {
+        shape: Shape::Moore,
+        radius: 1,
+        threshold: 4,
+        edges: Edges::Bounded,
+    };
+
+    /// Offsets of the neighbourhood, row by row for the built-in shapes.
+    pub fn offsets(&self) -> Vec<(isize, isize)> {
+        let r = self.radius as isize;
+        match &self.shape {
+            Shape::Custom(offsets) => offsets.clone(),
+            shape => (-r..=r)
+                .flat_map(|di| (-r..=r).map(move |dj| (di, dj)))
+                .filter(|&(di, dj)| {
+                    (di, dj) != (0, 0) && (*shape == Shape::Moore || di.abs() + dj.abs() <= r)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::PUZZLE
+    }
+}
+
+/// Offsets of the rules, resolved once.
+#[derive(Debug, Clone)]
+pub(crate) struct Neighbourhood {
+    pub(crate) offsets: Vec<(isize, isize)>,
+    pub(crate) edges: Edges,
+}
+
+impl Neighbourhood {
+    pub(crate) fn new(rules: &Rules) -> Self {
+        Self {
+            offsets: rules.offsets(),
+            edges: rules.edges,
+        }
+    }
+
+    /// The reflected neighbourhood, the neighbours of `pos` in it are the cells
+    /// whose neighbourhood contains `pos`.
+    pub(crate) fn reversed(&self) -> Self {
+        Self {
+            offsets: self.offsets.iter().map(|&(di, dj)| (-di, -dj)).collect(),
+            edges: self.edges,
+        }
+    }
+
+    /// Neighbours of `pos` in a warehouse of `height` rows and `width` columns,
+    /// a cell reached by several offsets is repeated.
+    pub(crate) fn neighbours(
+        &self,
+        (height, width): (usize, usize),
+        (i, j): Pos,
+    ) -> impl Iterator<Item = Pos> + '_ {
+        self.offsets
+            .iter()
+            .filter_map(move |&(di, dj)| match self.edges {
+                Edges::Bounded => {
+                    let pos = (i.checked_add_signed(di)?, j.checked_add_signed(dj)?);
+                    (pos.0 < height && pos.1 < width).then_some(pos)
+                }
+                Edges::Wrap => Some((
+                    (i as isize + di).rem_euclid(height as isize) as usize,
+                    (j as isize + dj).rem_euclid(width as isize) as usize,
+                )),
+            })
+    }
+
+    fn count_rolls(&self, grid: &Grid<Cell>, pos: Pos) -> usize {
+        self.neighbours((grid.height(), grid.width()), pos)
+            .filter(|&p| grid[p] == Cell::Roll)
+            .count()
+    }
+}
+
+/// Parses the warehouse map, `.` is an empty cell and `@` is a paper roll.
+pub fn parse_input<S: AsRef<str>>(lines: &[S]) -> Result<Grid<Cell>> {
+    Ok(Grid::parse(lines, parse_cell, CELLS)?)
+}
+
+/// Rolls a forklift can reach under `rules`, row by row.
+pub fn accessible(grid: &Grid<Cell>, rules: &Rules) -> Vec<Pos> {
+    let neighbourhood = Neighbourhood::new(rules);
+    grid.iter()
+        .filter(|&(pos, cell)| {
+            *cell == Cell::Roll && neighbourhood.count_rolls(grid, pos) < rules.threshold
         })
-        .collect::<Result<Vec<Vec<Cell>>>>()?;
-    let (n, m) = (grid.len(), grid[0].len());
-    let mut count = 0;
-    for i in 0..n {
-        for j in 0..m {
-            if grid[i][j] == Cell::Roll && count_rolls(&grid, i, j) < 4 {
-                count += 1;
+        .map(|(pos, _)| pos)
+        .collect()
+}
+
+/// Removal of the accessible rolls driven by a worklist, yields the rolls removed
+/// by every wave, row by row. Keeps the number of rolls around every roll and only
+/// rechecks the rolls next to removed ones, so a wave costs as much as its size.
+#[derive(Debug, Clone)]
+pub struct Removal {
+    grid: Grid<Cell>,
+    counts: Grid<u32>,
+    /// Cells whose count changes when a roll is removed.
+    watchers: Neighbourhood,
+    threshold: usize,
+    next: Vec<Pos>,
+}
+
+impl Removal {
+    pub fn new(grid: &Grid<Cell>, rules: &Rules) -> Self {
+        let neighbourhood = Neighbourhood::new(rules);
+        let mut removal = Self {
+            grid: grid.clone(),
+            counts: grid.map(|_| 0),
+            watchers: neighbourhood.reversed(),
+            threshold: rules.threshold,
+            next: Vec::new(),
+        };
+        for (pos, &cell) in grid.iter() {
+            if cell == Cell::Roll {
+                let count = neighbourhood.count_rolls(grid, pos);
+                removal.counts[pos] = count as u32;
+                if count < rules.threshold {
+                    removal.next.push(pos);
+                }
             }
         }
+        removal
+    }
+
+    /// The warehouse after the waves removed so far.
+    pub fn grid(&self) -> &Grid<Cell> {
+        &self.grid
     }
-    Ok(count)
 }
 
-pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<i32> {
-    let mut grid = lines
-        .into_iter()
-        .map(|line| {
-            line.as_ref()
-                .chars()
-                .map(parse_cell)
-                .collect::<Result<Vec<Cell>>>()
-        })
-        .collect::<Result<Vec<Vec<Cell>>>>()?;
-    let (n, m) = (grid.len(), grid[0].len());
-    let mut new_grid = vec![vec![Cell::Empty; m]; n];
-    let mut count = 0;
-    let mut is_changed = true;
-    while is_changed {
-        is_changed = false;
-        for i in 0..n {
-            for j in 0..m {
-                if grid[i][j] == Cell::Roll {
-                    if count_rolls(&grid, i, j) < 4 {
-                        count += 1;
-                        is_changed = true;
-                        new_grid[i][j] = Cell::Empty;
-                    } else {
-                        new_grid[i][j] = Cell::Roll;
+impl Iterator for Removal {
+    type Item = Vec<Pos>;
+
+    fn next(&mut self) -> Option<Vec<Pos>> {
+        let removed = mem::take(&mut self.next);
+        if removed.is_empty() {
+            return None;
+        }
+        for &pos in &removed {
+            self.grid[pos] = Cell::Empty;
+        }
+        let size = (self.grid.height(), self.grid.width());
+        for &pos in &removed {
+            for p in self.watchers.neighbours(size, pos) {
+                if self.grid[p] == Cell::Roll {
+                    // Counts only go down, so a roll crosses the threshold once.
+                    if self.counts[p] as usize == self.threshold {
+                        self.next.push(p);
                     }
-                } else {
-                    new_grid[i][j] = Cell::Empty;
+                    self.counts[p] -= 1;
                 }
             }
         }
-        mem::swap(&mut grid, &mut new_grid);
+        self.next.sort_unstable();
+        Some(removed)
+    }
+}
+
+/// A round of the removal, every roll accessible at its start is removed at once.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Wave {
+    /// Removed rolls, row by row.
+    pub removed: Vec<Pos>,
+    /// The warehouse after the removal.
+    pub grid: Grid<Cell>,
+}
+
+/// Iterator over the waves of the removal, ends when no roll is accessible.
+#[derive(Debug, Clone)]
+pub struct Waves {
+    removal: Removal,
+}
+
+impl Iterator for Waves {
+    type Item = Wave;
+
+    fn next(&mut self) -> Option<Wave> {
+        let removed = self.removal.next()?;
+        Some(Wave {
+            removed,
+            grid: self.removal.grid().clone(),
+        })
+    }
+}
+
+/// Simulates the removal of the rolls from `grid` under `rules`, one wave at a time.
+pub fn waves(grid: &Grid<Cell>, rules: &Rules) -> Waves {
+    Waves {
+        removal: Removal::new(grid, rules),
+    }
+}
+
+pub fn part1(grid: &Grid<Cell>, rules: &Rules) -> i32 {
+    accessible(grid, rules).len() as i32
+}
+
+pub fn part2(grid: &Grid<Cell>, rules: &Rules) -> i32 {
+    Removal::new(grid, rules)
+        .map(|removed| removed.len() as i32)
+        .sum()
+}
+
+pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<i32> {
+    Ok(part1(&parse_input(lines)?, &Rules::PUZZLE))
+}
+
+pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<i32> {
+    Ok(part2(&parse_input(lines)?, &Rules::PUZZLE))
+}
+
+/// Solution of day 4, a roll is accessible under `rules`.
+#[derive(Default)]
+pub struct Day04 {
+    pub rules: Rules,
+}
+
+impl Solution for Day04 {
+    type Input = Grid<Cell>;
+    type Error = Error;
+
+    fn parse<S: AsRef<str>>(&self, lines: &[S]) -> Result<Self::Input> {
+        parse_input(lines)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input, &self.rules).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input, &self.rules).into())
     }
-    Ok(count)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_core::OFFSETS_8;
+    use proptest::prelude::*;
     const DATA: &str = r"..@@.@@@@.
 @@@.@.@.@@
 @@@@@.@.@@
@@ -124,4 +337,122 @@ mod tests {
         let result = task2(&lines);
         assert_eq!(Ok(43), result);
     }
+    #[test]
+    fn waves_test() {
+        let grid = parse_input(&["@@@", "@@@", "@@@"]).unwrap();
+        let waves = waves(&grid, &Rules::PUZZLE).collect::<Vec<_>>();
+        assert_eq!(3, waves.len());
+        assert_eq!(vec![(0, 0), (0, 2), (2, 0), (2, 2)], waves[0].removed);
+        assert_eq!(".@.\n@@@\n.@.", waves[0].grid.to_string());
+        assert_eq!(vec![(0, 1), (1, 0), (1, 2), (2, 1)], waves[1].removed);
+        assert_eq!(vec![(1, 1)], waves[2].removed);
+        assert!(waves[2].grid.iter().all(|(_, &cell)| cell == Cell::Empty));
+        let lines = DATA.lines().collect::<Vec<_>>();
+        let counts = super::waves(&parse_input(&lines).unwrap(), &Rules::PUZZLE)
+            .map(|wave| wave.removed.len())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![13, 12, 7, 5, 2, 1, 1, 1, 1], counts);
+    }
+    /// Removal waves found by rescanning the whole grid every wave.
+    fn rescan(grid: &Grid<Cell>, rules: &Rules) -> Vec<Vec<Pos>> {
+        let mut grid = grid.clone();
+        let mut waves = Vec::new();
+        loop {
+            let removed = accessible(&grid, rules);
+            if removed.is_empty() {
+                return waves;
+            }
+            for &pos in &removed {
+                grid[pos] = Cell::Empty;
+            }
+            waves.push(removed);
+        }
+    }
+    /// Warehouses of up to 39 by 39 cells, with 10% to 90% of rolls.
+    fn grid() -> impl Strategy<Value = Grid<Cell>> {
+        (1usize..40, 1usize..40, 1u32..10)
+            .prop_flat_map(|(height, width, density)| {
+                let cell =
+                    prop_oneof![density => Just(Cell::Roll), 10 - density => Just(Cell::Empty)];
+                prop::collection::vec(prop::collection::vec(cell, width), height)
+            })
+            .prop_map(|rows| Grid::from_rows(rows).expect("rows have the same width"))
+    }
+
+    /// Built-in shapes of small radius and custom shapes of nearby offsets, bounded or wrapping.
+    fn rules() -> impl Strategy<Value = Rules> {
+        let shape = prop_oneof![
+            Just(Shape::Moore),
+            Just(Shape::VonNeumann),
+            prop::collection::vec((-2isize..=2, -2isize..=2), 0..6).prop_map(Shape::Custom),
+        ];
+        let edges = prop_oneof![Just(Edges::Bounded), Just(Edges::Wrap)];
+        (shape, 0usize..3, 0usize..12, edges).prop_map(|(shape, radius, threshold, edges)| Rules {
+            shape,
+            radius,
+            threshold,
+            edges,
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn removal_matches_rescan(grid in grid(), rules in rules()) {
+            let removal = Removal::new(&grid, &rules).collect::<Vec<_>>();
+            prop_assert_eq!(rescan(&grid, &rules), removal);
+        }
+    }
+    #[test]
+    fn rules_test() {
+        let von_neumann = Rules {
+            shape: Shape::VonNeumann,
+            radius: 1,
+            ..Rules::PUZZLE
+        };
+        assert_eq!(
+            vec![(-1, 0), (0, -1), (0, 1), (1, 0)],
+            von_neumann.offsets()
+        );
+        assert_eq!(OFFSETS_8.to_vec(), Rules::PUZZLE.offsets());
+        let moore2 = Rules {
+            radius: 2,
+            ..Rules::PUZZLE
+        };
+        assert_eq!(24, moore2.offsets().len());
+        let grid = parse_input(&["@@@", "@@@", "@@@"]).unwrap();
+        assert_eq!(4, part1(&grid, &Rules::PUZZLE));
+        // Only the centre has 4 orthogonal neighbours.
+        assert_eq!(8, part1(&grid, &von_neumann));
+        let wrap = Rules {
+            edges: Edges::Wrap,
+            ..Rules::PUZZLE
+        };
+        assert_eq!(0, part1(&grid, &wrap));
+        assert_eq!(0, part2(&grid, &wrap));
+        // More cells around a roll than a `u16` counts.
+        let moore128 = Rules {
+            radius: 128,
+            threshold: 9,
+            ..Rules::PUZZLE
+        };
+        assert_eq!(9, part2(&grid, &moore128));
+        let lines = DATA.lines().collect::<Vec<_>>();
+        let grid = parse_input(&lines).unwrap();
+        assert_eq!(43, part2(&grid, &Rules::PUZZLE));
+        assert_eq!(
+            0,
+            part2(
+                &grid,
+                &Rules {
+                    threshold: 0,
+                    ..Rules::PUZZLE
+                }
+            )
+        );
+    }
+    #[test]
+    fn parse_error_test() {
+        let error = ParseError::new(2, ".#.", 1..2, "`.` or `@`");
+        assert_eq!(Err(Error::ParseError(error)), task1(&["@@@", ".#."]));
+    }
 }
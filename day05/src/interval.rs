@@ -0,0 +1,241 @@
+use std::fmt;
+
+/// Integer types an [`IntervalSet`] can hold.
+pub trait Integer: Copy + Ord + fmt::Debug {
+    const MIN: Self;
+    const MAX: Self;
+
+    /// `self + 1`, `None` at the maximum.
+    fn checked_next(self) -> Option<Self>;
+
+    /// `self - 1`, `None` at the minimum.
+    fn checked_prev(self) -> Option<Self>;
+
+    /// Number of values in `start..=end`, with `start <= end`.
+    fn count(start: Self, end: Self) -> u128;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(impl Integer for $t {
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+
+            fn checked_next(self) -> Option<Self> {
+                self.checked_add(1)
+            }
+
+            fn checked_prev(self) -> Option<Self> {
+                self.checked_sub(1)
+            }
+
+            fn count(start: Self, end: Self) -> u128 {
+                (end as i128 - start as i128 + 1) as u128
+            }
+        })*
+    };
+}
+
+impl_integer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// Set of integers stored as inclusive ranges, sorted, disjoint and not adjacent,
+/// so every set has a single representation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set of the values in any of the inclusive `ranges`, a range with `start > end` is empty.
+    pub fn from_ranges(ranges: impl IntoIterator<Item = (T, T)>) -> Self {
+        let mut ranges = ranges
+            .into_iter()
+            .filter(|(start, end)| start <= end)
+            .collect::<Vec<_>>();
+        ranges.sort_unstable();
+        let mut merged: Vec<(T, T)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                // Overlapping or adjacent, nothing follows a range ending at the maximum.
+                Some(last) if last.1.checked_next().is_none_or(|next| start <= next) => {
+                    last.1 = last.1.max(end);
+                }
+                _ => merged.push((start, end)),
+            }
+        }
+        Self { ranges: merged }
+    }
+
+    /// The inclusive ranges, sorted.
+    pub fn ranges(&self) -> &[(T, T)] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of values in the set.
+    pub fn total_len(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|&(start, end)| T::count(start, end))
+            .sum()
+    }
+
+    /// The range containing `value`, found by binary search.
+    pub fn range_of(&self, value: T) -> Option<(T, T)> {
+        let i = self.ranges.partition_point(|&(start, _)| start <= value);
+        let range = *self.ranges.get(i.checked_sub(1)?)?;
+        (value <= range.1).then_some(range)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.range_of(value).is_some()
+    }
+
+    pub fn insert(&mut self, start: T, end: T) {
+        *self = Self::from_ranges(self.ranges.iter().copied().chain([(start, end)]));
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::from_ranges(self.ranges.iter().chain(&other.ranges).copied())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        let mut ranges = Vec::new();
+        while let (Some(&&x), Some(&&y)) = (a.peek(), b.peek()) {
+            let (start, end) = (x.0.max(y.0), x.1.min(y.1));
+            if start <= end {
+                ranges.push((start, end));
+            }
+            // The range ending first cannot meet any later range of the other set.
+            if x.1 <= y.1 {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.intersection(&other.complement(T::MIN, T::MAX))
+    }
+
+    /// Values of `low..=high` that are not in the set.
+    pub fn complement(&self, low: T, high: T) -> Self {
+        let mut ranges = Vec::new();
+        // First value not known to be in the set, `None` past the maximum.
+        let mut next = Some(low);
+        for &(start, end) in &self.ranges {
+            let Some(first) = next.filter(|&first| first <= high) else {
+                break;
+            };
+            if end < first {
+                continue;
+            }
+            if start > first {
+                let last = start.checked_prev().expect("`start` is above `first`");
+                ranges.push((first, last.min(high)));
+            }
+            next = end.checked_next();
+        }
+        if let Some(first) = next.filter(|&first| first <= high) {
+            ranges.push((first, high));
+        }
+        Self { ranges }
+    }
+}
+
+impl<T: Integer> FromIterator<(T, T)> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(ranges: I) -> Self {
+        Self::from_ranges(ranges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn interval_set_test() {
+        let set =
+            IntervalSet::from_ranges([(10u64, 14), (3, 5), (16, 20), (12, 18), (6, 6), (9, 1)]);
+        assert_eq!(&[(3, 6), (10, 20)], set.ranges());
+        assert_eq!(15, set.total_len());
+        assert_eq!(Some((10, 20)), set.range_of(15));
+        assert!(set.contains(6) && !set.contains(7) && !set.contains(2) && !set.contains(21));
+        let other = IntervalSet::from_ranges([(5, 11), (19, 30)]);
+        assert_eq!(&[(3, 30)], set.union(&other).ranges());
+        assert_eq!(
+            &[(5, 6), (10, 11), (19, 20)],
+            set.intersection(&other).ranges()
+        );
+        assert_eq!(&[(3, 4), (12, 18)], set.difference(&other).ranges());
+        assert_eq!(&[(0, 2), (7, 9), (21, 25)], set.complement(0, 25).ranges());
+        assert_eq!(&[(8, 8)], set.complement(8, 8).ranges());
+        assert!(set.complement(11, 19).is_empty());
+        let full = IntervalSet::from_ranges([(0, u64::MAX), (5, 6)]);
+        assert_eq!(1 << 64, full.total_len());
+        assert!(full.complement(0, u64::MAX).is_empty());
+        let mut set = IntervalSet::new();
+        set.insert(u64::MAX, u64::MAX);
+        set.insert(0, u64::MAX - 1);
+        assert_eq!(full, set);
+    }
+
+    /// Sets of `i8` built from up to 4 ranges, along with the set of their values.
+    fn set() -> impl Strategy<Value = (IntervalSet<i8>, BTreeSet<i8>)> {
+        let range =
+            (any::<i8>(), 0i8..40).prop_map(|(start, len)| (start, start.saturating_add(len)));
+        prop::collection::vec(range, 0..5).prop_map(|ranges| {
+            let values = ranges
+                .iter()
+                .flat_map(|&(start, end)| start..=end)
+                .collect::<BTreeSet<_>>();
+            (IntervalSet::from_ranges(ranges), values)
+        })
+    }
+
+    /// The values of `set`, also checks that the set is normalised.
+    fn to_values(set: &IntervalSet<i8>) -> BTreeSet<i8> {
+        assert_eq!(&IntervalSet::from_ranges(set.ranges().to_vec()), set);
+        set.ranges()
+            .iter()
+            .flat_map(|&(start, end)| start..=end)
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn set_matches_brute_force((a, a_values) in set(), (b, b_values) in set()) {
+            prop_assert_eq!(a_values.len() as u128, a.total_len());
+            prop_assert!((-128..=127).all(|x| a.contains(x) == a_values.contains(&x)));
+            let union: BTreeSet<_> = a_values.union(&b_values).copied().collect();
+            prop_assert_eq!(union, to_values(&a.union(&b)));
+            let intersection: BTreeSet<_> = a_values.intersection(&b_values).copied().collect();
+            prop_assert_eq!(intersection, to_values(&a.intersection(&b)));
+            let difference: BTreeSet<_> = a_values.difference(&b_values).copied().collect();
+            prop_assert_eq!(difference, to_values(&a.difference(&b)));
+            let complement: BTreeSet<_> = (-20..=20).filter(|x| !a_values.contains(x)).collect();
+            prop_assert_eq!(complement, to_values(&a.complement(-20, 20)));
+        }
+    }
+}
@@ -0,0 +1,7 @@
+mod interval;
+mod report;
+mod task;
+
+pub use interval::*;
+pub use report::*;
+pub use task::*;
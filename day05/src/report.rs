@@ -0,0 +1,195 @@
+use crate::{Database, IntervalSet};
+
+/// A fresh range of the database with the line it is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceRange {
+    pub line: usize,
+    pub start: u64,
+    pub end: u64,
+}
+
+impl SourceRange {
+    pub fn contains(&self, id: u64) -> bool {
+        self.start <= id && id <= self.end
+    }
+}
+
+impl Database {
+    /// The fresh ranges with their line numbers, in input order.
+    pub fn source_ranges(&self) -> impl Iterator<Item = SourceRange> + '_ {
+        self.ranges
+            .iter()
+            .enumerate()
+            .map(|(i, &(start, end))| SourceRange {
+                line: i + 1,
+                start,
+                end,
+            })
+    }
+}
+
+/// The fresh ranges sorted by start, to find the ranges containing an ID.
+#[derive(Debug, Clone)]
+pub struct RangeIndex {
+    ranges: Vec<SourceRange>,
+    /// Largest end among the ranges up to every index.
+    max_ends: Vec<u64>,
+}
+
+impl RangeIndex {
+    pub fn new(db: &Database) -> Self {
+        let mut ranges = db.source_ranges().collect::<Vec<_>>();
+        ranges.sort_by_key(|r| (r.start, r.line));
+        let max_ends = ranges
+            .iter()
+            .scan(0, |max, r| {
+                *max = r.end.max(*max);
+                Some(*max)
+            })
+            .collect();
+        Self { ranges, max_ends }
+    }
+
+    /// Ranges containing `id`, by line. Only the ranges starting at or before `id`
+    /// are scanned, from the last one back until no earlier range reaches `id`.
+    pub fn matching(&self, id: u64) -> Vec<SourceRange> {
+        let last = self.ranges.partition_point(|r| r.start <= id);
+        let mut matches = (0..last)
+            .rev()
+            .take_while(|&i| self.max_ends[i] >= id)
+            .map(|i| self.ranges[i])
+            .filter(|r| r.contains(id))
+            .collect::<Vec<_>>();
+        matches.sort_by_key(|r| r.line);
+        matches
+    }
+}
+
+/// Status of an available ingredient, spoiled when no range contains it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdReport {
+    pub id: u64,
+    pub matches: Vec<SourceRange>,
+}
+
+impl IdReport {
+    pub fn is_fresh(&self) -> bool {
+        !self.matches.is_empty()
+    }
+}
+
+/// Ranges whose every ID is in some other range, by line. Each copy of a duplicated
+/// range is covered by the others, so all of them are flagged.
+pub fn redundant_ranges(db: &Database) -> Vec<SourceRange> {
+    // The IDs in at least two ranges: an ID of a range is in another one exactly
+    // when it is covered twice. The ends are exclusive, hence `u128`.
+    let mut events = db
+        .ranges
+        .iter()
+        .filter(|(start, end)| start <= end)
+        .flat_map(|&(start, end)| [(start as u128, 1), (end as u128 + 1, -1)])
+        .collect::<Vec<(u128, i32)>>();
+    events.sort_unstable();
+    let mut depth = 0;
+    let mut twice = Vec::new();
+    for (i, &(x, delta)) in events.iter().enumerate() {
+        depth += delta;
+        let next = events.get(i + 1).map_or(x, |&(next, _)| next);
+        if depth >= 2 && next > x {
+            twice.push((x as u64, (next - 1) as u64));
+        }
+    }
+    let twice = IntervalSet::from_ranges(twice);
+    db.source_ranges()
+        .filter(|r| r.start <= r.end)
+        .filter(|r| twice.range_of(r.start).is_some_and(|(_, end)| r.end <= end))
+        .collect()
+}
+
+/// Freshness of every available ingredient with the ranges containing it,
+/// and the ranges that could be removed one at a time without changing any answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub ids: Vec<IdReport>,
+    pub redundant: Vec<SourceRange>,
+}
+
+pub fn report(db: &Database) -> Report {
+    let index = RangeIndex::new(db);
+    let ids = db
+        .ids
+        .iter()
+        .map(|&id| IdReport {
+            id,
+            matches: index.matching(id),
+        })
+        .collect();
+    Report {
+        ids,
+        redundant: redundant_ranges(db),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, part1};
+
+    fn range(line: usize, start: u64, end: u64) -> SourceRange {
+        SourceRange { line, start, end }
+    }
+
+    #[test]
+    fn report_test() {
+        let lines = [
+            "3-5", "10-14", "16-20", "12-18", "", "1", "5", "8", "11", "17", "32",
+        ];
+        let db = parse_input(&lines).unwrap();
+        let report = report(&db);
+        let matches = report
+            .ids
+            .iter()
+            .map(|r| (r.id, r.matches.iter().map(|m| m.line).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        let expected = vec![
+            (1, vec![]),
+            (5, vec![1]),
+            (8, vec![]),
+            (11, vec![2]),
+            (17, vec![3, 4]),
+            (32, vec![]),
+        ];
+        assert_eq!(expected, matches);
+        let fresh = report.ids.iter().filter(|r| r.is_fresh()).count();
+        assert_eq!(part1(&db), fresh);
+        assert_eq!(Vec::<SourceRange>::new(), report.redundant);
+    }
+
+    #[test]
+    fn redundant_test() {
+        let lines = [
+            "1-10", "2-3", "5-12", "11-20", "30-40", "30-40", "9-7", "", "9",
+        ];
+        let db = parse_input(&lines).unwrap();
+        let expected = vec![
+            range(2, 2, 3),
+            range(3, 5, 12),
+            range(5, 30, 40),
+            range(6, 30, 40),
+        ];
+        assert_eq!(expected, redundant_ranges(&db));
+        // Against checking every range with the set of the others.
+        for r in db.source_ranges().filter(|r| r.start <= r.end) {
+            let others = db.source_ranges().filter(|o| o.line != r.line);
+            let others = IntervalSet::from_ranges(others.map(|o| (o.start, o.end)));
+            let covered = IntervalSet::from_ranges([(r.start, r.end)])
+                .difference(&others)
+                .is_empty();
+            assert_eq!(covered, expected.contains(&r), "{:?}", r);
+        }
+        let index = RangeIndex::new(&db);
+        assert_eq!(vec![range(1, 1, 10), range(3, 5, 12)], index.matching(9));
+        let db = parse_input(&["0-18446744073709551615", "5-5", "", "0"]).unwrap();
+        assert_eq!(vec![range(2, 5, 5)], redundant_ranges(&db));
+    }
+}
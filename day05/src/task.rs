@@ -1,70 +1,101 @@
+use crate::IntervalSet;
+use aoc_core::{Answer, ParseError, Solution};
+
 #[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
 pub enum Error {
-    #[error("Parse error")]
-    ParseError(#[from] std::num::ParseIntError),
-    #[error("Format error")]
-    FormatError,
+    #[error("Parse error: {0}")]
+    ParseError(#[from] ParseError),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<i32> {
+/// Ingredient database: the fresh ID ranges in input order, so the range at index `i`
+/// is on line `i + 1`, and the available ingredient IDs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Database {
+    pub ranges: Vec<(u64, u64)>,
+    pub ids: Vec<u64>,
+}
+
+pub fn parse_input<S: AsRef<str>>(lines: &[S]) -> Result<Database> {
     let split_pos = lines
         .iter()
         .position(|s| s.as_ref().is_empty())
-        .ok_or(Error::FormatError)?;
+        .ok_or_else(|| {
+            let last = lines.last().map_or("", |s| s.as_ref());
+            ParseError::at_end(
+                lines.len().max(1),
+                last,
+                "an empty line before the ingredient IDs",
+            )
+        })?;
     let (pairs, nums) = (&lines[..split_pos], &lines[split_pos + 1..]);
-    let mut ranges = pairs
+    let parse_id = |line: usize, text: &str, token: &str| {
+        token
+            .parse::<u64>()
+            .map_err(|_| ParseError::at(line, text, token, "an ingredient ID"))
+    };
+    let ranges = pairs
         .iter()
-        .map(|pair| {
-            let p = pair.as_ref().split_once('-').unwrap();
-            (p.0.parse::<u64>().unwrap(), p.1.parse::<u64>().unwrap())
+        .enumerate()
+        .map(|(i, pair)| {
+            let text = pair.as_ref();
+            let (start, end) = text
+                .split_once('-')
+                .ok_or_else(|| ParseError::whole_line(i + 1, text, "a `start-end` range"))?;
+            Ok((parse_id(i + 1, text, start)?, parse_id(i + 1, text, end)?))
         })
-        .collect::<Vec<(u64, u64)>>();
-    ranges.sort();
-    let result = nums
-        .into_iter()
-        .map(|s| s.as_ref().parse::<u64>().unwrap())
-        .filter(|u| ranges.iter().any(|(start, end)| start <= u && u <= end))
-        .count();
-    Ok(result as _)
+        .collect::<Result<Vec<(u64, u64)>>>()?;
+    let ids = nums
+        .iter()
+        .enumerate()
+        .map(|(i, s)| Ok(parse_id(split_pos + i + 2, s.as_ref(), s.as_ref())?))
+        .collect::<Result<Vec<u64>>>()?;
+    Ok(Database { ranges, ids })
 }
 
-pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
-    let split_pos = lines
-        .iter()
-        .position(|s| s.as_ref().is_empty())
-        .ok_or(Error::FormatError)?;
-    let pairs = &lines[..split_pos];
-    let mut ranges = pairs
-        .iter()
-        .map(|pair| {
-            let p = pair.as_ref().split_once('-').unwrap();
-            (p.0.parse::<u64>().unwrap(), p.1.parse::<u64>().unwrap())
-        })
-        .collect::<Vec<(u64, u64)>>();
-    ranges.sort();
-    let mut unique_ranges: Vec<(u64, u64)> = Vec::new();
-    for r in ranges {
-        if let Some(last) = unique_ranges.pop() {
-            if last.1 >= r.0 {
-                if last.1 <= r.1 {
-                    unique_ranges.push((last.0, r.1));
-                } else {
-                    unique_ranges.push(last);
-                }
-            } else {
-                unique_ranges.push(last);
-                unique_ranges.push(r);
-            }
-        } else {
-            unique_ranges.push(r);
-        }
+impl Database {
+    /// The IDs in any of the fresh ranges.
+    pub fn fresh(&self) -> IntervalSet<u64> {
+        IntervalSet::from_ranges(self.ranges.iter().copied())
+    }
+}
+
+pub fn part1(db: &Database) -> usize {
+    let fresh = db.fresh();
+    db.ids.iter().filter(|&&id| fresh.contains(id)).count()
+}
+
+/// Number of fresh IDs, up to 2^64 when every ID is fresh.
+pub fn part2(db: &Database) -> u128 {
+    db.fresh().total_len()
+}
+
+pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<i32> {
+    Ok(part1(&parse_input(lines)?) as _)
+}
+
+pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u128> {
+    Ok(part2(&parse_input(lines)?))
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = Database;
+    type Error = Error;
+
+    fn parse<S: AsRef<str>>(&self, lines: &[S]) -> Result<Self::Input> {
+        parse_input(lines)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        Ok(Answer::Number(part2(input) as i128))
     }
-    let result = unique_ranges
-        .into_iter()
-        .fold(0, |acc, r| acc + r.1 - r.0 + 1);
-    Ok(result)
 }
 
 #[cfg(test)]
@@ -93,4 +124,35 @@ mod tests {
         let result = task2(&lines);
         assert_eq!(Ok(14), result);
     }
+    #[test]
+    fn parse_input_test() {
+        let lines = DATA.lines().collect::<Vec<_>>();
+        let result = parse_input(&lines);
+        assert_eq!(
+            Ok(Database {
+                ranges: vec![(3, 5), (10, 14), (16, 20), (12, 18)],
+                ids: vec![1, 5, 8, 11, 17, 32],
+            }),
+            result
+        );
+    }
+    #[test]
+    fn overlap_test() {
+        let lines = ["1-3", "4-4", "2-3", "0-18446744073709551615", "", "4"];
+        assert_eq!(Ok(1 << 64), task2(&lines));
+        assert_eq!(Ok(4), task2(&["1-3", "4-4", "2-3", "9-7", "", "9"]));
+        assert_eq!(Ok(0), task1(&["1-3", "4-4", "2-3", "9-7", "", "9"]));
+    }
+    #[test]
+    fn parse_error_test() {
+        let error = ParseError::at_end(2, "10-14", "an empty line before the ingredient IDs");
+        assert_eq!(Err(Error::ParseError(error)), task1(&["3-5", "10-14"]));
+        let error = ParseError::whole_line(2, "10", "a `start-end` range");
+        assert_eq!(
+            Err(Error::ParseError(error)),
+            task2(&["3-5", "10", "", "4"])
+        );
+        let error = ParseError::new(4, "x", 0..1, "an ingredient ID");
+        assert_eq!(Err(Error::ParseError(error)), task1(&["3-5", "", "4", "x"]));
+    }
 }
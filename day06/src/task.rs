@@ -1,113 +1,206 @@
+use aoc_core::{Answer, Grid, ParseError, Solution};
+use std::ops::Range;
+
 #[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
 pub enum Error {
-    #[error("Parse error")]
-    ParseError(#[from] std::num::ParseIntError),
-    #[error("Format error")]
-    FormatError,
+    #[error("Parse error: {0}")]
+    ParseError(#[from] ParseError),
+    #[error("A result does not fit in 64 bits")]
+    Overflow,
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-fn transpose<S: AsRef<str>>(matrix: &[S]) -> Vec<Vec<u8>> {
-    let n = matrix.len();
-    let m = matrix[0].as_ref().len();
-    let mut result = vec![vec![b' '; n]; m];
-    for i in 0..n {
-        for (j, b) in matrix[i].as_ref().bytes().enumerate() {
-            result[j][i] = b;
-        }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Plus,
+    Mult,
+}
+
+/// A single problem of the worksheet: the block of columns between two blank columns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    /// Digits and spaces of the block as written on the sheet, without the operator row.
+    pub cells: Grid<u8>,
+    pub op: Operator,
+}
+
+impl Problem {
+    /// Numbers written left to right, one per row.
+    pub fn row_numbers(&self) -> Result<Vec<u64>> {
+        numbers(self.cells.rows().map(|row| row.iter()))
+    }
+
+    /// Numbers written top to bottom, one per column.
+    pub fn column_numbers(&self) -> Result<Vec<u64>> {
+        numbers(self.cells.columns())
     }
-    result
 }
 
-pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
-    let split_pos = lines
-        .iter()
-        .position(|s| s.as_ref().starts_with('*'))
-        .ok_or(Error::FormatError)?;
-    let nums = lines[..split_pos]
-        .iter()
-        .map(|s| {
-            s.as_ref()
-                .split_ascii_whitespace()
-                .map(|ss| ss.parse::<u64>().unwrap())
-                .collect::<Vec<u64>>()
+/// The number spelled by the digits among `cells`, `None` without digits.
+/// A number too large for a `u64` is an error with the index of the cell where it overflows.
+fn number<'a>(cells: impl Iterator<Item = &'a u8>) -> std::result::Result<Option<u64>, usize> {
+    cells
+        .enumerate()
+        .filter(|(_, b)| b.is_ascii_digit())
+        .try_fold(None, |acc: Option<u64>, (k, b)| {
+            let value = acc.unwrap_or(0).checked_mul(10);
+            let value = value.and_then(|value| value.checked_add((b - b'0') as u64));
+            value.map(Some).ok_or(k)
         })
-        .collect::<Vec<Vec<u64>>>();
-    let commands = lines[split_pos]
-        .as_ref()
-        .split_ascii_whitespace()
-        .filter(|s| !s.is_empty())
-        .collect::<Vec<&str>>();
-    let mut result = 0u64;
-    for (i, &cmd) in commands.iter().enumerate() {
-        result += match cmd {
-            "+" => nums.iter().fold(0u64, |acc, v| acc + v[i]),
-            "*" => nums.iter().fold(1u64, |acc, v| acc * v[i]),
-            _ => 0,
-        }
+}
+
+fn numbers<'a, I>(lines: impl Iterator<Item = I>) -> Result<Vec<u64>>
+where
+    I: Iterator<Item = &'a u8>,
+{
+    let numbers = lines.map(|cells| number(cells).map_err(|_| Error::Overflow));
+    numbers.filter_map(Result::transpose).collect()
+}
+
+fn parse_operator(block: &[u8]) -> Option<Operator> {
+    let mut ops = block.iter().filter(|b| **b != b' ');
+    match (ops.next(), ops.next()) {
+        (Some(b'+'), None) => Some(Operator::Plus),
+        (Some(b'*'), None) => Some(Operator::Mult),
+        _ => None,
     }
-    Ok(result)
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Operator {
-    Plus,
-    Mult,
+/// Checks that every character of the line is allowed, so the rest of the parser can work on bytes.
+fn check_chars(line: usize, text: &str, allowed: &[char], expected: &str) -> Result<()> {
+    match text.char_indices().find(|(_, c)| !allowed.contains(c)) {
+        Some((j, c)) => Err(ParseError::new(line, text, j..j + c.len_utf8(), expected).into()),
+        None => Ok(()),
+    }
 }
 
-fn parse(s: &Vec<u8>) -> Option<(u64, Option<Operator>)> {
-    if s.is_empty() || s.iter().all(|c| *c == b' ') {
-        return None;
+/// Checks that every number of the problem in `columns` fits in a `u64`, read along the rows
+/// and along the columns, so the solvers only have to check their results.
+fn check_numbers<S: AsRef<str>>(
+    lines: &[S],
+    sheet: &Grid<u8>,
+    columns: Range<usize>,
+) -> Result<()> {
+    const EXPECTED: &str = "a number that fits in 64 bits";
+    let rows = 0..sheet.height() - 1;
+    for i in rows.clone() {
+        let row = &sheet.row(i)[columns.clone()];
+        if number(row.iter()).is_err() {
+            let digits = || (columns.clone()).filter(|&j| sheet[(i, j)].is_ascii_digit());
+            let span = digits().next().unwrap_or(0)..digits().next_back().map_or(0, |j| j + 1);
+            return Err(ParseError::new(i + 1, lines[i].as_ref(), span, EXPECTED).into());
+        }
     }
-    let mut num = 0u64;
-    let mut op = None;
-    for b in s {
-        match b {
-            b'+' => {
-                op = Some(Operator::Plus);
-            }
-            b'*' => {
-                op = Some(Operator::Mult);
-            }
-            n if n.is_ascii_digit() => {
-                num = num * 10 + (n - b'0') as u64;
-            }
-            _ => {}
+    for j in columns {
+        if let Err(i) = number(sheet.column(j).take(rows.len())) {
+            return Err(ParseError::new(i + 1, lines[i].as_ref(), j..j + 1, EXPECTED).into());
         }
     }
-    Some((num, op))
+    Ok(())
 }
 
-fn compute(nums: &Vec<u64>, operation: Operator) -> u64 {
-    match operation {
-        Operator::Plus => nums.iter().sum(),
-        Operator::Mult => nums.iter().product(),
+/// Splits the worksheet into problems, the last line holds the operators.
+pub fn parse_input<S: AsRef<str>>(lines: &[S]) -> Result<Vec<Problem>> {
+    const DIGITS: [char; 11] = [' ', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+    let (ops_line, rows) = lines
+        .split_last()
+        .ok_or_else(|| ParseError::at_end(1, "", "a line of operators"))?;
+    let ops_line = ops_line.as_ref();
+    for (i, row) in rows.iter().enumerate() {
+        check_chars(i + 1, row.as_ref(), &DIGITS, "a digit or a space")?;
     }
+    check_chars(
+        lines.len(),
+        ops_line,
+        &[' ', '+', '*'],
+        "`+`, `*` or a space",
+    )?;
+    let width = lines.iter().map(|l| l.as_ref().len()).max().unwrap_or(0);
+    let pad = |line: &str| {
+        let mut bytes = line.as_bytes().to_vec();
+        bytes.resize(width, b' ');
+        bytes
+    };
+    let sheet = lines
+        .iter()
+        .map(|line| pad(line.as_ref()))
+        .collect::<Vec<_>>();
+    let sheet = Grid::from_rows(sheet).expect("rows are padded to the same width");
+    let ops_row = sheet.height() - 1;
+    let mut problems = Vec::new();
+    let mut start = 0;
+    for end in 0..=width {
+        if end == width || sheet.column(end).all(|b| *b == b' ') {
+            if start < end {
+                let op = parse_operator(&sheet.row(ops_row)[start..end]).ok_or_else(|| {
+                    let span = start.min(ops_line.len())..end.min(ops_line.len());
+                    ParseError::new(lines.len(), ops_line, span, "a single `+` or `*` operator")
+                })?;
+                check_numbers(lines, &sheet, start..end)?;
+                problems.push(Problem {
+                    cells: sheet.crop(0..ops_row, start..end),
+                    op,
+                });
+            }
+            start = end + 1;
+        }
+    }
+    Ok(problems)
+}
+
+fn compute(nums: &[u64], operation: Operator) -> Result<u64> {
+    let result = match operation {
+        Operator::Plus => nums.iter().try_fold(0u64, |acc, &n| acc.checked_add(n)),
+        Operator::Mult => nums.iter().try_fold(1u64, |acc, &n| acc.checked_mul(n)),
+    };
+    result.ok_or(Error::Overflow)
+}
+
+/// Sums the results of the problems, `numbers` reads the numbers of a problem.
+fn grand_total(
+    problems: &[Problem],
+    numbers: impl Fn(&Problem) -> Result<Vec<u64>>,
+) -> Result<u64> {
+    problems.iter().try_fold(0u64, |sum, p| {
+        let result = compute(&numbers(p)?, p.op)?;
+        sum.checked_add(result).ok_or(Error::Overflow)
+    })
+}
+
+pub fn part1(problems: &[Problem]) -> Result<u64> {
+    grand_total(problems, Problem::row_numbers)
+}
+
+pub fn part2(problems: &[Problem]) -> Result<u64> {
+    grand_total(problems, Problem::column_numbers)
+}
+
+pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
+    part1(&parse_input(lines)?)
 }
 
 pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
-    let lines = transpose(lines);
-    let mut last_op = None;
-    let mut nums = Vec::new();
-    let mut result = 0;
-    for l in lines {
-        match parse(&l) {
-            None => {
-                result += compute(&nums, last_op.unwrap());
-                nums.clear();
-                last_op = None;
-            }
-            Some((x, maybe_op)) => {
-                nums.push(x);
-                if let Some(op) = maybe_op {
-                    last_op = Some(op);
-                }
-            }
-        }
+    part2(&parse_input(lines)?)
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = Vec<Problem>;
+    type Error = Error;
+
+    fn parse<S: AsRef<str>>(&self, lines: &[S]) -> Result<Self::Input> {
+        parse_input(lines)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        part2(input).map(Answer::from)
     }
-    result += compute(&nums, last_op.unwrap());
-    Ok(result)
 }
 
 #[cfg(test)]
@@ -126,4 +219,36 @@ mod tests {
         let result = task2(&lines);
         assert_eq!(Ok(3263827), result);
     }
+    #[test]
+    fn parse_error_test() {
+        let error = ParseError::new(2, " 4x", 2..3, "a digit or a space");
+        assert_eq!(
+            Err(Error::ParseError(error)),
+            task1(&["12 3", " 4x", "*  +"])
+        );
+        let error = ParseError::new(3, "*+ +", 0..2, "a single `+` or `*` operator");
+        assert_eq!(
+            Err(Error::ParseError(error)),
+            task2(&["12 3", "45 6", "*+ +"])
+        );
+    }
+    #[test]
+    fn overflow_test() {
+        let expected = "a number that fits in 64 bits";
+        let line = "  99999999999999999999";
+        let error = ParseError::new(1, line, 2..22, expected);
+        let lines = [line, "1111111111111111111111", "+"];
+        assert_eq!(Err(Error::ParseError(error)), task1(&lines));
+        let mut lines = vec!["0 9"; 21];
+        lines.push("+ *");
+        let error = ParseError::new(20, "0 9", 2..3, expected);
+        assert_eq!(Err(Error::ParseError(error)), task2(&lines));
+        let max = "18446744073709551615";
+        assert_eq!(Ok(u64::MAX), task1(&[max, "+"]));
+        assert_eq!(Err(Error::Overflow), task1(&[max, "1", "+"]));
+        let lines = ["4294967296", "4294967296", "*"];
+        assert_eq!(Err(Error::Overflow), task1(&lines));
+        let lines = [format!("{} 1", max), format!("{:21}+", "+")];
+        assert_eq!(Err(Error::Overflow), task1(&lines));
+    }
 }
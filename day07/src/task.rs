@@ -1,62 +1,131 @@
+use aoc_core::{Answer, Grid, ParseError, Pos, Solution};
+use std::fmt;
+
 #[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
 pub enum Error {
-    #[error("Parse error")]
-    ParseError(#[from] std::num::ParseIntError),
+    #[error("Parse error: {0}")]
+    ParseError(#[from] ParseError),
     #[error("Format error")]
     FormatError,
 }
 
-fn traverse(grid: &Vec<Vec<u8>>, i: usize, j: usize, cache: &mut Vec<Vec<u64>>) -> u64 {
-    if cache[i][j] != 0 {
-        return cache[i][j];
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Cell {
+    Empty,
+    Splitter,
+    Start,
+    Beam,
+}
+
+fn parse_cell(c: char) -> Option<Cell> {
+    match c {
+        '.' => Some(Cell::Empty),
+        '^' => Some(Cell::Splitter),
+        'S' => Some(Cell::Start),
+        _ => None,
     }
-    if i == grid.len() - 1 {
-        cache[i][j] = 1;
-        return 1;
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cell::Empty => write!(f, "."),
+            Cell::Splitter => write!(f, "^"),
+            Cell::Start => write!(f, "S"),
+            Cell::Beam => write!(f, "|"),
+        }
     }
-    if grid[i + 1][j] == b'.' {
-        cache[i][j] = traverse(grid, i + 1, j, cache);
-        return cache[i][j];
+}
+
+fn traverse(grid: &Grid<Cell>, (i, j): Pos, cache: &mut Grid<u64>) -> Result<u64> {
+    if cache[(i, j)] != 0 {
+        return Ok(cache[(i, j)]);
     }
-    if grid[i + 1][j] == b'^' {
-        cache[i][j] = traverse(grid, i + 1, j - 1, cache) + traverse(grid, i + 1, j + 1, cache);
-        return cache[i][j];
+    if i == grid.height() - 1 {
+        cache[(i, j)] = 1;
+        return Ok(1);
     }
-    unreachable!("traverse");
+    cache[(i, j)] = match grid[(i + 1, j)] {
+        Cell::Empty => traverse(grid, (i + 1, j), cache)?,
+        Cell::Splitter => {
+            let left = grid.offset((i + 1, j), (0, -1)).ok_or(Error::FormatError)?;
+            let right = grid.offset((i + 1, j), (0, 1)).ok_or(Error::FormatError)?;
+            traverse(grid, left, cache)? + traverse(grid, right, cache)?
+        }
+        _ => return Err(Error::FormatError),
+    };
+    Ok(cache[(i, j)])
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
+/// Parses the tachyon manifold diagram: a rectangle of `.` and `^` with the start `S` in the first row.
+pub fn parse_input<S: AsRef<str>>(lines: &[S]) -> Result<Grid<Cell>> {
+    let first = lines
+        .first()
+        .map(|l| l.as_ref())
+        .ok_or_else(|| ParseError::at_end(1, "", "a manifold diagram"))?;
+    let grid = Grid::parse(lines, parse_cell, "`.`, `^` or `S`")?;
+    if !grid.row(0).contains(&Cell::Start) {
+        return Err(ParseError::at_end(1, first, "the start `S`").into());
+    }
+    Ok(grid)
+}
 
-pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<u32> {
-    let mut grid = lines
-        .into_iter()
-        .map(|l| l.as_ref().bytes().collect::<Vec<u8>>())
-        .collect::<Vec<Vec<u8>>>();
+pub fn part1(grid: &Grid<Cell>) -> u32 {
+    let mut grid = grid.clone();
     let mut result = 0;
-    for i in 1..grid.len() {
-        for j in 0..grid[i].len() {
-            if grid[i][j] == b'^' {
-                if grid[i - 1][j] == b'|' {
-                    result += 1;
-                    grid[i][j - 1] = b'|';
-                    grid[i][j + 1] = b'|';
+    for (i, j) in grid.positions().skip(grid.width()) {
+        let above = grid[(i - 1, j)];
+        if grid[(i, j)] == Cell::Splitter {
+            if above == Cell::Beam {
+                result += 1;
+                for side in [-1, 1] {
+                    if let Some(pos) = grid.offset((i, j), (0, side)) {
+                        grid[pos] = Cell::Beam;
+                    }
                 }
-            } else if grid[i - 1][j] == b'|' || grid[i - 1][j] == b'S' {
-                grid[i][j] = b'|';
             }
+        } else if above == Cell::Beam || above == Cell::Start {
+            grid[(i, j)] = Cell::Beam;
         }
     }
-    Ok(result)
+    result
+}
+
+pub fn part2(grid: &Grid<Cell>) -> Result<u64> {
+    let mut cache = Grid::filled(grid.width(), grid.height(), 0);
+    let start = (0..grid.width())
+        .find(|&j| grid.get((0, j)) == Some(&Cell::Start))
+        .ok_or(Error::FormatError)?;
+    traverse(grid, (0, start), &mut cache)
+}
+
+pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<u32> {
+    Ok(part1(&parse_input(lines)?))
 }
 
 pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<u64> {
-    let grid = lines
-        .into_iter()
-        .map(|l| l.as_ref().bytes().collect::<Vec<u8>>())
-        .collect::<Vec<Vec<u8>>>();
-    let mut cache = vec![vec![0; grid[0].len()]; grid.len()];
-    let start = grid[0].iter().position(|b| *b == b'S').unwrap();
-    Ok(traverse(&grid, 0, start, &mut cache))
+    part2(&parse_input(lines)?)
+}
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = Grid<Cell>;
+    type Error = Error;
+
+    fn parse<S: AsRef<str>>(&self, lines: &[S]) -> Result<Self::Input> {
+        parse_input(lines)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        part2(input).map(Answer::from)
+    }
 }
 
 #[cfg(test)]
@@ -90,4 +159,13 @@ mod tests {
         let result = task2(&lines);
         assert_eq!(Ok(40), result);
     }
+    #[test]
+    fn malformed_input_test() {
+        let error = ParseError::new(2, "..|", 2..3, "`.`, `^` or `S`");
+        assert_eq!(Err(Error::ParseError(error)), task1(&[".S.", "..|"]));
+        let error = ParseError::whole_line(2, "..", "a row of 3 cells");
+        assert_eq!(Err(Error::ParseError(error)), task2(&[".S.", ".."]));
+        assert_eq!(Ok(1), task1(&["S.", "..", "^."]));
+        assert_eq!(Err(Error::FormatError), task2(&["S.", "..", "^."]));
+    }
 }
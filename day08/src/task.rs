@@ -1,43 +1,75 @@
-use std::collections::{BinaryHeap, HashSet};
+use aoc_core::{Answer, ParseError, Solution};
+use std::cmp::Reverse;
+use std::collections::HashSet;
 
 #[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
 pub enum Error {
-    #[error("Parse error")]
-    ParseError(#[from] std::num::ParseIntError),
-    #[error("Format error")]
-    FormatError,
+    #[error("Parse error: {0}")]
+    ParseError(#[from] ParseError),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-fn distance(a: &(i64, i64, i64), b: &(i64, i64, i64)) -> i64 {
-    (a.0 - b.0).pow(2) + (a.1 - b.1).pow(2) + (a.2 - b.2).pow(2)
+fn distance(a: &(i64, i64, i64), b: &(i64, i64, i64)) -> i128 {
+    let d = |x: i64, y: i64| (x as i128 - y as i128).pow(2);
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
 }
 
-pub fn task1<S: AsRef<str>>(lines: &[S], mut count: u32) -> Result<u32> {
-    fn find_circuite(circuites: &Vec<HashSet<usize>>, key: usize) -> Option<usize> {
-        circuites.iter().position(|set| set.contains(&key))
+fn find_circuite(circuites: &[HashSet<usize>], key: usize) -> Option<usize> {
+    circuites.iter().position(|set| set.contains(&key))
+}
+
+fn parse_point(line: usize, text: &str) -> std::result::Result<(i64, i64, i64), ParseError> {
+    let tokens = text.split(',').collect::<Vec<_>>();
+    if tokens.len() != 3 {
+        return Err(ParseError::whole_line(line, text, "`x,y,z` coordinates"));
     }
+    // Coordinates are limited to 32 bits, so that distances and products of them cannot overflow.
+    let coordinate = |token: &str| {
+        token
+            .parse::<i32>()
+            .map(i64::from)
+            .map_err(|_| ParseError::at(line, text, token, "a coordinate"))
+    };
+    Ok((
+        coordinate(tokens[0])?,
+        coordinate(tokens[1])?,
+        coordinate(tokens[2])?,
+    ))
+}
+
+/// Junction boxes and every pair of them, closest pairs first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Playground {
+    pub points: Vec<(i64, i64, i64)>,
+    pub pairs: Vec<(usize, usize)>,
+}
+
+pub fn parse_input<S: AsRef<str>>(lines: &[S]) -> Result<Playground> {
     let points = lines
         .iter()
-        .map(|l| {
-            let v = l
-                .as_ref()
-                .split(',')
-                .map(|x| x.parse::<i64>().unwrap())
-                .collect::<Vec<_>>();
-            (v[0], v[1], v[2])
-        })
-        .collect::<Vec<(i64, i64, i64)>>();
+        .enumerate()
+        .map(|(i, l)| parse_point(i + 1, l.as_ref()))
+        .collect::<std::result::Result<Vec<(i64, i64, i64)>, ParseError>>()?;
     let n = points.len();
-    let mut distances: BinaryHeap<(i64, usize, usize)> = BinaryHeap::new();
+    let mut distances = Vec::with_capacity(n * n.saturating_sub(1) / 2);
     for i in 0..n {
         for j in (i + 1)..n {
-            distances.push((-distance(&points[i], &points[j]), i, j));
+            distances.push((distance(&points[i], &points[j]), Reverse(i), Reverse(j)));
         }
     }
+    // Same order as popping a max-heap of (-distance, i, j).
+    distances.sort_unstable();
+    let pairs = distances
+        .into_iter()
+        .map(|(_, Reverse(i), Reverse(j))| (i, j))
+        .collect();
+    Ok(Playground { points, pairs })
+}
+
+pub fn part1(playground: &Playground, count: u32) -> u32 {
     let mut circuits: Vec<HashSet<usize>> = Vec::new();
-    while let Some((d, i, j)) = distances.pop() {
+    for &(i, j) in playground.pairs.iter().take(count as usize) {
         let id1 = find_circuite(&circuits, i);
         let id2 = find_circuite(&circuits, j);
         match (id1, id2) {
@@ -56,40 +88,16 @@ pub fn task1<S: AsRef<str>>(lines: &[S], mut count: u32) -> Result<u32> {
                 circuits.push(HashSet::from([i, j]));
             }
         }
-        count -= 1;
-        if count == 0 {
-            break;
-        }
     }
-    circuits.sort_by_key(|c| std::cmp::Reverse(c.len()));
-    Ok(circuits.iter().take(3).map(|c| c.len()).product::<usize>() as _)
+    circuits.sort_by_key(|c| Reverse(c.len()));
+    circuits.iter().take(3).map(|c| c.len()).product::<usize>() as _
 }
 
-pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<i64> {
-    fn find_circuite(circuites: &Vec<HashSet<usize>>, key: usize) -> Option<usize> {
-        circuites.iter().position(|set| set.contains(&key))
-    }
-    let points = lines
-        .iter()
-        .map(|l| {
-            let v = l
-                .as_ref()
-                .split(',')
-                .map(|x| x.parse::<i64>().unwrap())
-                .collect::<Vec<_>>();
-            (v[0], v[1], v[2])
-        })
-        .collect::<Vec<(i64, i64, i64)>>();
-    let n = points.len();
-    let mut distances: BinaryHeap<(i64, usize, usize)> = BinaryHeap::new();
-    for i in 0..n {
-        for j in (i + 1)..n {
-            distances.push((-distance(&points[i], &points[j]), i, j));
-        }
-    }
+pub fn part2(playground: &Playground) -> i64 {
+    let points = &playground.points;
     let mut circuits: Vec<HashSet<usize>> = Vec::new();
     let mut ans = 0;
-    while let Some((_, i, j)) = distances.pop() {
+    for &(i, j) in &playground.pairs {
         let id1 = find_circuite(&circuits, i);
         let id2 = find_circuite(&circuits, j);
         match (id1, id2) {
@@ -113,7 +121,43 @@ pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<i64> {
             }
         }
     }
-    Ok(ans)
+    ans
+}
+
+pub fn task1<S: AsRef<str>>(lines: &[S], count: u32) -> Result<u32> {
+    Ok(part1(&parse_input(lines)?, count))
+}
+
+pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<i64> {
+    Ok(part2(&parse_input(lines)?))
+}
+
+/// Solution of day 8, `connections` is the number of closest pairs connected in part 1.
+pub struct Day08 {
+    pub connections: u32,
+}
+
+impl Default for Day08 {
+    fn default() -> Self {
+        Self { connections: 1000 }
+    }
+}
+
+impl Solution for Day08 {
+    type Input = Playground;
+    type Error = Error;
+
+    fn parse<S: AsRef<str>>(&self, lines: &[S]) -> Result<Self::Input> {
+        parse_input(lines)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input, self.connections).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input).into())
+    }
 }
 
 #[cfg(test)]
@@ -151,4 +195,24 @@ mod tests {
         let result = task2(&lines);
         assert_eq!(Ok(25272), result);
     }
+    #[test]
+    fn solution_test() {
+        let solution = Day08 { connections: 10 };
+        let input = solution.parse(&DATA.lines().collect::<Vec<_>>()).unwrap();
+        assert_eq!(Ok(Answer::Number(40)), solution.part1(&input));
+        assert_eq!(Ok(Answer::Number(25272)), solution.part2(&input));
+    }
+    #[test]
+    fn parse_error_test() {
+        let error = ParseError::whole_line(2, "57,618", "`x,y,z` coordinates");
+        assert_eq!(
+            Err(Error::ParseError(error)),
+            task2(&["162,817,812", "57,618"])
+        );
+        let error = ParseError::new(1, "1,99999999999,3", 2..13, "a coordinate");
+        assert_eq!(
+            Err(Error::ParseError(error)),
+            task1(&["1,99999999999,3"], 1)
+        );
+    }
 }
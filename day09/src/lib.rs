@@ -0,0 +1,3 @@
+mod task;
+
+pub use task::*;
@@ -1,40 +1,58 @@
+use aoc_core::{Answer, ParseError, Solution};
 use itertools::Itertools;
 use glam::I64Vec2;
 
 #[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
 pub enum Error {
-    #[error("Parse error")]
-    ParseError(#[from] std::num::ParseIntError),
+    #[error("Parse error: {0}")]
+    ParseError(#[from] ParseError),
     #[error("Format error")]
     FormatError,
+    #[error("An area does not fit in 64 bits")]
+    Overflow,
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-fn square(a: &I64Vec2, b: &I64Vec2) -> i64 {
-    ((a.x - b.x).abs() + 1) * ((a.y - b.y).abs() + 1)
+/// Area of the rectangle with opposite corners `a` and `b`, its sides fit in an `i128`.
+fn square(a: &I64Vec2, b: &I64Vec2) -> Result<i64> {
+    let side = |a: i64, b: i64| (a as i128 - b as i128).abs() + 1;
+    side(a.x, b.x)
+        .checked_mul(side(a.y, b.y))
+        .and_then(|area| i64::try_from(area).ok())
+        .ok_or(Error::Overflow)
 }
 
-fn parse_point<S: AsRef<str>>(line: &S) -> Result<I64Vec2> {
-    let (x, y) = line.as_ref().split_once(',').ok_or(Error::FormatError)?;
-    Ok(I64Vec2::new(x.parse::<i64>()?, y.parse::<i64>()?))
+fn parse_point(line: usize, text: &str) -> std::result::Result<I64Vec2, ParseError> {
+    let (x, y) = text
+        .split_once(',')
+        .ok_or_else(|| ParseError::whole_line(line, text, "`x,y` coordinates"))?;
+    let coordinate = |token: &str| {
+        token
+            .parse::<i64>()
+            .map_err(|_| ParseError::at(line, text, token, "a coordinate"))
+    };
+    Ok(I64Vec2::new(coordinate(x)?, coordinate(y)?))
 }
 
-fn parse_input<S: AsRef<str>>(lines: &[S]) -> Result<Vec<I64Vec2>> {
-    lines.iter().map(parse_point).collect()
+/// Parses the red tiles, one `x,y` pair per line.
+pub fn parse_input<S: AsRef<str>>(lines: &[S]) -> Result<Vec<I64Vec2>> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| Ok(parse_point(i + 1, line.as_ref())?))
+        .collect()
 }
 
-pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<i64> {
-    let points = parse_input(lines)?;
+pub fn part1(points: &[I64Vec2]) -> Result<i64> {
     let max_square = points.iter()
         .tuple_combinations()
-        .map(|(a, b)| square(a, b))
-        .max()
+        .try_fold(None, |max, (a, b)| -> Result<_> { Ok(max.max(Some(square(a, b)?))) })?
         .ok_or(Error::FormatError)?;
     Ok(max_square)
 }
 
-pub fn task2<S: AsRef<str>>(input: &[S]) -> Result<i64> {
+pub fn part2(points: &[I64Vec2]) -> Result<i64> {
     fn is_valid(line: &(I64Vec2, I64Vec2), a: &I64Vec2, b: &I64Vec2) -> bool {
         let (line_start, line_end) = line;
         let left = a.x.max(b.x) <= line_start.x.min(line_end.x);
@@ -43,14 +61,15 @@ pub fn task2<S: AsRef<str>>(input: &[S]) -> Result<i64> {
         let bottom = a.y.min(b.y) >= line_start.y.max(line_end.y);
         left || right || top || bottom
     }
-    let points = parse_input(input)?;
     let lines = points.iter()
         .cloned()
         .circular_tuple_windows()
         .collect::<Vec<(I64Vec2, I64Vec2)>>();
     let max_box = points.iter()
         .tuple_combinations()
-        .map(|(a, b)| (a, b, square(a, b)))
+        .map(|(a, b)| Ok((a, b, square(a, b)?)))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
         .sorted_unstable_by_key(|(_, _, square)| *square)
         .rev()
         .find(|(a, b, _)| {
@@ -60,6 +79,33 @@ pub fn task2<S: AsRef<str>>(input: &[S]) -> Result<i64> {
     Ok(max_box.2)
 }
 
+pub fn task1<S: AsRef<str>>(lines: &[S]) -> Result<i64> {
+    part1(&parse_input(lines)?)
+}
+
+pub fn task2<S: AsRef<str>>(lines: &[S]) -> Result<i64> {
+    part2(&parse_input(lines)?)
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = Vec<I64Vec2>;
+    type Error = Error;
+
+    fn parse<S: AsRef<str>>(&self, lines: &[S]) -> Result<Self::Input> {
+        parse_input(lines)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        part2(input).map(Answer::from)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,4 +129,19 @@ mod tests {
         let result = task2(&lines);
         assert_eq!(Ok(24), result);
     }
+    #[test]
+    fn parse_error_test() {
+        let error = ParseError::new(2, "11;1", 0..4, "`x,y` coordinates");
+        assert_eq!(Err(Error::ParseError(error)), task1(&["7,1", "11;1"]));
+        let error = ParseError::new(1, "7,-", 2..3, "a coordinate");
+        assert_eq!(Err(Error::ParseError(error)), task2(&["7,-"]));
+    }
+    #[test]
+    fn overflow_test() {
+        let lines = ["-9223372036854775808,0", "9223372036854775807,0"];
+        assert_eq!(Err(Error::Overflow), task1(&lines));
+        assert_eq!(Err(Error::Overflow), task2(&lines));
+        let lines = ["-4611686018427387904,0", "4611686018427387902,0"];
+        assert_eq!(Ok(i64::MAX), task1(&lines));
+    }
 }
@@ -1,35 +1,7 @@
-mod input;
-mod task;
+use aoc_core::Part;
+use std::process::ExitCode;
 
-use input::{read_file, read_stdin};
-use std::env;
-use std::io;
-use task::*;
-
-enum Mode {
-    File(String),
-    Stdin,
-}
-
-fn main() -> io::Result<()> {
-    let mode = env::args()
-        .nth(1)
-        .map(|arg| Mode::File(arg))
-        .unwrap_or(Mode::Stdin);
-    let data = match mode {
-        Mode::File(file_path) => read_file(&file_path)?,
-        Mode::Stdin => read_stdin()?,
-    };
-
-    // match task1(&data) {
-    //     Ok(result) => println!("result1 {}", result),
-    //     Err(error) => println!("error {}", error),
-    // }
-
-    match task2(&data) {
-        Ok(result) => println!("result2 {}", result),
-        Err(error) => println!("error {}", error),
-    }
-
-    Ok(())
+fn main() -> ExitCode {
+    // Part one tries every permutation of the buttons and is too slow for the real input.
+    aoc_core::run_parts(10, &day10::Day10, &[Part::Two])
 }
@@ -1,56 +1,81 @@
+use aoc_core::{Answer, ParseError, Solution};
 use itertools::Itertools;
 use std::convert::TryFrom;
+use std::str::FromStr;
 use std::collections::VecDeque;
 
 #[derive(thiserror::Error, Debug, Clone, Eq, PartialEq)]
 pub enum Error {
-    #[error("Parse error")]
-    ParseError(#[from] std::num::ParseIntError),
-    #[error("Format error")]
-    FormatError,
+    #[error("Parse error: {0}")]
+    ParseError(#[from] ParseError),
     #[error("No solution found")]
     NoSolution,
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Indicator lights, button wirings and joltage requirements of a machine.
+pub type Machine = (Vec<State>, Vec<Vec<usize>>, Vec<i16>);
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
-enum State {
+pub enum State {
     On = 1,
     Off = 0,
 }
 
 impl TryFrom<char> for State {
-    type Error = Error;
-    fn try_from(c: char) -> Result<Self> {
+    type Error = char;
+    fn try_from(c: char) -> std::result::Result<Self, char> {
         match c {
             '#' => Ok(State::On),
             '.' => Ok(State::Off),
-            _ => Err(Error::FormatError),
+            _ => Err(c),
         }
     }
 }
 
-fn parse_line(line: &str) -> Result<(Vec<State>, Vec<Vec<usize>>, Vec<i16>)> {
+fn parse_list<T: FromStr>(line: usize, text: &str, list: &str, expected: &str) -> std::result::Result<Vec<T>, ParseError> {
+    list.split(',').map(|s| s.parse::<T>().map_err(|_| ParseError::at(line, text, s, expected))).collect()
+}
+
+/// Lights and buttons are bit masks in a `u32`.
+const MAX_LIGHTS: usize = 32;
+
+fn parse_line(line: usize, text: &str) -> std::result::Result<Machine, ParseError> {
     let mut states: Option<Vec<State>> = None;
-    let mut buttons = Vec::new();
+    // Every button with its list, to point at a light index once all the lights are known.
+    let mut buttons: Vec<(&str, Vec<usize>)> = Vec::new();
     let mut joltage: Option<Vec<i16>> = None;
-    for part in line.split_whitespace() {
+    for part in text.split_whitespace() {
         if let Some(stripped) = part.strip_prefix("[").and_then(|s| s.strip_suffix("]")) {
-            states = Some(stripped.chars().map(|c| State::try_from(c)).collect::<Result<Vec<_>>>()?);
+            states = Some(stripped.char_indices().map(|(j, c)| {
+                State::try_from(c).map_err(|c| ParseError::at(line, text, &stripped[j..j + c.len_utf8()], "`#` or `.`"))
+            }).collect::<std::result::Result<Vec<_>, _>>()?);
+            if let Some((j, _)) = stripped.char_indices().nth(MAX_LIGHTS) {
+                return Err(ParseError::at(line, text, &stripped[j..], "at most 32 lights"));
+            }
         } else if let Some(stripped) = part.strip_prefix("(").and_then(|s| s.strip_suffix(")")) {
-            buttons.push(stripped.split(',').map(|s| s.parse::<usize>().map_err(Error::from)).collect::<Result<Vec<_>>>()?);
+            buttons.push((stripped, parse_list(line, text, stripped, "a light index")?));
         } else if let Some(stripped) = part.strip_prefix("{").and_then(|s| s.strip_suffix("}")) {
-            joltage = Some(stripped.split(',').map(|s| s.parse::<i16>().map_err(Error::from)).collect::<Result<Vec<_>>>()?);
+            joltage = Some(parse_list(line, text, stripped, "a joltage level")?);
         } else {
-            return Err(Error::FormatError);
+            return Err(ParseError::at(line, text, part, "`[lights]`, `(buttons)` or `{joltage}`"));
+        }
+    }
+    let states = states.ok_or_else(|| ParseError::at_end(line, text, "`[lights]`"))?;
+    let joltage = joltage.ok_or_else(|| ParseError::at_end(line, text, "`{joltage}`"))?;
+    // A button toggles a light and raises its joltage, both must exist.
+    let lights = states.len().min(joltage.len());
+    for (list, indices) in &buttons {
+        if let Some((s, _)) = list.split(',').zip(indices).find(|(_, light)| **light >= lights) {
+            return Err(ParseError::at(line, text, s, format!("a light index below {}", lights)));
         }
     }
-    Ok((states.ok_or(Error::FormatError)?, buttons, joltage.ok_or(Error::FormatError)?))
+    Ok((states, buttons.into_iter().map(|(_, indices)| indices).collect(), joltage))
 }
 
-fn parse_input<S: AsRef<str>>(input: &[S]) -> Result<Vec<(Vec<State>, Vec<Vec<usize>>, Vec<i16>)>> {
-    input.iter().map(|line| parse_line(line.as_ref())).collect()
+pub fn parse_input<S: AsRef<str>>(input: &[S]) -> Result<Vec<Machine>> {
+    input.iter().enumerate().map(|(i, line)| Ok(parse_line(i + 1, line.as_ref())?)).collect()
 }
 
 fn state_to_u32(state: &[State]) -> u32 {
@@ -61,6 +86,7 @@ fn buttons_to_u32(buttons: &[usize]) -> u32 {
     buttons.iter().fold(0, |acc, button| acc | (1 << *button))
 }
 
+#[allow(dead_code)]
 fn bfs(buttons: &[usize], target_joltage: Vec<i16>) -> Result<u32> {
     let mut queue = VecDeque::new();
     queue.push_back((0, target_joltage));
@@ -87,7 +113,7 @@ impl Helper {
         Self { buttons }
     }
 
-    fn decrease_joltage(joltage: &mut Vec<i16>, button: usize) {
+    fn decrease_joltage(joltage: &mut [i16], button: usize) {
         let mut button = button;
         let mut i = 0;
         while button != 0 {
@@ -99,7 +125,7 @@ impl Helper {
         }
     }
 
-    fn increase_joltage(joltage: &mut Vec<i16>, button: usize) {
+    fn increase_joltage(joltage: &mut [i16], button: usize) {
         let mut button = button;
         let mut i = 0;
         while button != 0 {
@@ -111,7 +137,7 @@ impl Helper {
         }
     }
 
-    fn dfs(&self, steps: u32, current_joltage: &mut Vec<i16>) -> u32 {
+    fn dfs(&self, steps: u32, current_joltage: &mut [i16]) -> u32 {
         if current_joltage.iter().all(|a| *a == 0) {
             return steps;
         }
@@ -127,10 +153,12 @@ impl Helper {
     }
 }
 
-pub fn task1<S: AsRef<str>>(input: &[S]) -> Result<i64> {
-    let machines = parse_input(input)?;
-    let result = machines.iter().map(|(target_state, buttons, _joltage)| {
+pub fn part1(machines: &[Machine]) -> Result<i64> {
+    machines.iter().map(|(target_state, buttons, _joltage)| {
         let target_state = state_to_u32(target_state);
+        if target_state == 0 {
+            return Ok(0);
+        }
         let buttons = buttons.iter().map(|buttons| buttons_to_u32(buttons)).collect::<Vec<_>>();
         let mut min_steps = usize::MAX;
         for buttons_permutation in buttons.iter().permutations(buttons.len()) {
@@ -143,20 +171,50 @@ pub fn task1<S: AsRef<str>>(input: &[S]) -> Result<i64> {
                 }
             }
         }
-        min_steps as i64
-    }).sum();
-    Ok(result)
+        if min_steps == usize::MAX {
+            return Err(Error::NoSolution);
+        }
+        Ok(min_steps as i64)
+    }).sum()
 }
 
-pub fn task2<S: AsRef<str>>(input: &[S]) -> Result<u32> {
-    let machines = parse_input(input)?;
-    let result = machines.into_iter().map(|(_, buttons, mut joltage)| {
+pub fn part2(machines: &[Machine]) -> Result<u64> {
+    machines.iter().map(|(_, buttons, joltage)| {
         let buttons = buttons.iter().map(|button| buttons_to_u32(button) as usize).collect::<Vec<_>>();
         // bfs(&buttons, joltage.clone()).unwrap()
         let helper = Helper::new(buttons);
-        helper.dfs(0, &mut joltage)
-    }).sum();
-    Ok(result)
+        match helper.dfs(0, &mut joltage.clone()) {
+            u32::MAX => Err(Error::NoSolution),
+            steps => Ok(steps as u64),
+        }
+    }).sum()
+}
+
+pub fn task1<S: AsRef<str>>(input: &[S]) -> Result<i64> {
+    part1(&parse_input(input)?)
+}
+
+pub fn task2<S: AsRef<str>>(input: &[S]) -> Result<u64> {
+    part2(&parse_input(input)?)
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<Machine>;
+    type Error = Error;
+
+    fn parse<S: AsRef<str>>(&self, lines: &[S]) -> Result<Self::Input> {
+        parse_input(lines)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        part2(input).map(Answer::from)
+    }
 }
 
 #[cfg(test)]
@@ -199,4 +257,35 @@ mod tests {
         let result = task2(&lines);
         assert_eq!(Ok(33), result);
     }
+    #[test]
+    fn parse_error_test() {
+        let line = "[.#x.] (3) {3,5,4,7}";
+        let error = ParseError::new(1, line, 3..4, "`#` or `.`");
+        assert_eq!(Err(Error::ParseError(error)), parse_input(&[line]));
+        let line = "[.##.] (3) (1,a) {3,5,4,7}";
+        let error = ParseError::new(1, line, 14..15, "a light index");
+        assert_eq!(Err(Error::ParseError(error)), parse_input(&[line]));
+        let line = "[.##.] (3)";
+        let error = ParseError::at_end(1, line, "`{joltage}`");
+        assert_eq!(Err(Error::ParseError(error)), parse_input(&[line]));
+        let line = "[.##.] (3) (1,32) {3,5,4,7}";
+        let error = ParseError::new(1, line, 14..16, "a light index below 4");
+        assert_eq!(Err(Error::ParseError(error)), parse_input(&[line]));
+        let line = "[.##.] (0) (31) {3}";
+        let error = ParseError::new(1, line, 12..14, "a light index below 1");
+        assert_eq!(Err(Error::ParseError(error)), task2(&[line]));
+        let line = "(0,3) {3,5,4} [.##.]";
+        let error = ParseError::new(1, line, 3..4, "a light index below 3");
+        assert_eq!(Err(Error::ParseError(error)), parse_input(&[line]));
+        let line = format!("[{}] (3) {{3}}", ".".repeat(34));
+        let error = ParseError::new(1, &line, 33..35, "at most 32 lights");
+        assert_eq!(Err(Error::ParseError(error)), parse_input(&[&line]));
+    }
+    #[test]
+    fn no_solution_test() {
+        assert_eq!(Err(Error::NoSolution), task1(&["[#] {1}"]));
+        assert_eq!(Err(Error::NoSolution), task2(&["[#] {1}", "[#] {1}"]));
+        assert_eq!(Err(Error::NoSolution), task2(&["[#] (0) {-1}"]));
+        assert_eq!(Ok(0), task1(&["[..] (0) {0,0}"]));
+    }
 }